@@ -3,7 +3,7 @@ use regex::Regex;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufWriter, Write};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use std::{env, io};
 
@@ -11,23 +11,104 @@ fn main() -> io::Result<()> {
     let mut args = env::args();
     args.next();
 
-    let input_name = args.next().expect("Please provide a filename as argument");
-    let input_path = PathBuf::from(input_name.clone());
-    let buffer = fs::read_to_string(input_name)?;
-    if input_path.is_file() {
-        let mut jack_t = jack_tokenizer::new(&buffer);
+    // a file is analyzed on its own, a folder analyzes every .jack file in it
+    let mut input_name = None;
+    let mut output_dir = None;
+    let mut overwrite = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--overwrite" => overwrite = true,
+            "-o" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| usage_error("-o needs a folder"))?;
+                output_dir = Some(PathBuf::from(dir));
+            }
+            _ if arg.starts_with('-') => {
+                return Err(usage_error(&format!("unknown option {}", arg)));
+            }
+            _ => input_name = Some(arg),
+        }
+    }
+    let input_name = input_name
+        .ok_or_else(|| usage_error("Usage: compiler_p10 [-o DIR] [--overwrite] FILE.jack|DIR"))?;
+    let input_path = PathBuf::from(input_name);
 
-        while jack_t.has_more_tokens() {
-            jack_t.advance();
-            jack_t.write_token_file();
+    let mut paths = Vec::new();
+    if input_path.is_dir() {
+        for entry in fs::read_dir(&input_path)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "jack") {
+                paths.push(path);
+            }
         }
+        paths.sort();
+    } else {
+        paths.push(input_path);
+    }
+
+    // FooT.xml and Foo.xml go next to Foo.jack unless -o says otherwise
+    let mut outputs = Vec::new();
+    for path in &paths {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| usage_error("bad file name"))?;
+        let dir = match &output_dir {
+            Some(output_dir) => output_dir.as_path(),
+            None => path.parent().unwrap_or(Path::new("")),
+        };
+        outputs.push((
+            path,
+            dir.join(format!("{stem}T.xml")),
+            dir.join(format!("{stem}.xml")),
+        ));
+    }
+    // the course keeps its reference XML next to the sources; never replace it by accident
+    if !overwrite
+        && let Some(existing) = outputs
+            .iter()
+            .flat_map(|(_, tokens, tree)| [tokens, tree])
+            .find(|xml| xml.exists())
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists; write elsewhere with -o DIR or pass --overwrite",
+                existing.display()
+            ),
+        ));
+    }
+
+    if let Some(output_dir) = &output_dir {
+        fs::create_dir_all(output_dir)?;
+    }
+    for (path, tokens_path, tree_path) in &outputs {
+        analyze(path, tokens_path, tree_path)?;
+    }
 
-        let mut c_engine = compilation_engine::new(jack_t.tokens).unwrap();
+    Ok(())
+}
+
+fn usage_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string())
+}
 
-        c_engine.compile_class()?;
+/// Writes the tokens of the class in `path` to `tokens_path` and its parse tree to
+/// `tree_path`.
+fn analyze(path: &Path, tokens_path: &Path, tree_path: &Path) -> io::Result<()> {
+    let buffer = fs::read_to_string(path)?;
+    let mut jack_t = jack_tokenizer::new(&buffer, tokens_path)?;
+
+    while jack_t.has_more_tokens() {
+        jack_t.advance();
+        jack_t.write_token_file();
     }
+    jack_t.finish_token_file()?;
 
-    Ok(())
+    let mut c_engine = compilation_engine::new(jack_t.tokens, tree_path)?;
+
+    c_engine.compile_class()
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -65,15 +146,15 @@ struct jack_tokenizer {
 }
 
 impl jack_tokenizer {
-    fn new(file: &str) -> Self {
+    fn new(file: &str, tokens_path: &Path) -> io::Result<Self> {
         let tokens: Vec<Token> = Vec::new();
 
-        let file_tokens = OpenOptions::new()
+        let mut file_tokens = OpenOptions::new()
             .truncate(true)
             .write(true)
             .create(true)
-            .open("tokens.xml")
-            .unwrap();
+            .open(tokens_path)?;
+        file_tokens.write_all(b"<tokens>\n")?;
 
         let mut j_tokenizer = Self {
             tokens,
@@ -81,33 +162,41 @@ impl jack_tokenizer {
             pos: 0,
             tokens_file: Some(file_tokens),
         };
-        let re = Regex::new(r#""[^"]*"|\w+|[^\w\s]"#).unwrap();
-
-        for line in file.lines() {
-            // need to add sanitization for /* block level comments */
-            let sanitized_line = line.split("//").next().unwrap_or("").trim();
-
-            if !sanitized_line.is_empty() {
-                re.find_iter(sanitized_line).for_each(|x| {
-                    let token = x.as_str().to_string();
-
-                    j_tokenizer.current_token = Some(token.clone());
-                    let token_kind = j_tokenizer.token_type();
-                    j_tokenizer.tokens.push(Token {
-                        value: token,
-                        kind: token_kind,
-                    });
-                });
+        // comments are matched too, so `//` inside a string stays part of the string
+        let re = Regex::new(r#""[^"\n]*"|//[^\n]*|(?s:/\*.*?\*/)|\w+|[^\w\s]"#).unwrap();
+
+        for x in re.find_iter(file) {
+            let token = x.as_str();
+            if token.starts_with("//") || token.starts_with("/*") {
+                continue;
             }
+
+            j_tokenizer.current_token = Some(token.to_string());
+            let token_kind = j_tokenizer.token_type();
+            // a string constant is written without its quotes
+            let value = match token_kind {
+                TOKEN_TYPE::STRING_CONST => token[1..token.len() - 1].to_string(),
+                _ => token.to_string(),
+            };
+            j_tokenizer.tokens.push(Token {
+                value,
+                kind: token_kind,
+            });
         }
+        j_tokenizer.current_token = None;
 
-        j_tokenizer
+        Ok(j_tokenizer)
     }
 
     fn write_token_file(&mut self) {
-        let token_type = self.token_type_as_str();
-
-        let token = self.current_token.as_ref().unwrap();
+        let current = &self.tokens[self.pos - 1];
+        let token_type = current.kind.as_str();
+        let token = current
+            .value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;");
 
         let write_token = format!("<{token_type}> {token} </{token_type}>\n");
         let _ = self
@@ -117,6 +206,14 @@ impl jack_tokenizer {
             .write_all(write_token.as_bytes());
     }
 
+    /// Closes the `<tokens>` element once every token is written.
+    fn finish_token_file(&mut self) -> io::Result<()> {
+        match self.tokens_file.as_mut() {
+            Some(f) => f.write_all(b"</tokens>\n"),
+            None => Ok(()),
+        }
+    }
+
     fn has_more_tokens(&self) -> bool {
         self.pos < self.tokens.len()
     }
@@ -183,16 +280,6 @@ impl jack_tokenizer {
             _ => TOKEN_TYPE::IDENTIFIER,
         }
     }
-
-    fn token_type_as_str(&self) -> &str {
-        match self.token_type() {
-            TOKEN_TYPE::IDENTIFIER => "identifier",
-            TOKEN_TYPE::KEYWORD => "keyword",
-            TOKEN_TYPE::STRING_CONST => "stringConstant",
-            TOKEN_TYPE::INT_CONST => "integerConstant",
-            TOKEN_TYPE::SYMBOL => "symbol",
-        }
-    }
 }
 
 struct compilation_engine {
@@ -203,13 +290,12 @@ struct compilation_engine {
 }
 
 impl compilation_engine {
-    fn new(tokens: Vec<Token>) -> io::Result<Self> {
+    fn new(tokens: Vec<Token>, tree_path: &Path) -> io::Result<Self> {
         let file = OpenOptions::new()
             .truncate(true)
             .write(true)
             .create(true)
-            .open("output.xml")
-            .unwrap();
+            .open(tree_path)?;
 
         let writer = Self {
             file: Some(BufWriter::new(file)),
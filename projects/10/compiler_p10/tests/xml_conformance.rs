@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod xml_diff;
+
+use xml_diff::structural_diff;
+
+/// An empty folder for one test, under cargo's scratch space.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Analyzes `projects/10/<program>` into a scratch folder, then compares every reference
+/// `Foo.xml` and `FooT.xml` with what the analyzer wrote.
+fn check_program(program: &str) {
+    let source_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(program);
+    let dir = scratch_dir(&format!("xml-{program}"));
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler_p10"))
+        .arg("-o")
+        .arg(&dir)
+        .arg(&source_dir)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut failures = Vec::new();
+    let mut compared = 0;
+    for entry in fs::read_dir(&source_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "xml") {
+            continue;
+        }
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let expected = fs::read_to_string(&path).unwrap();
+        let actual = fs::read_to_string(dir.join(name)).unwrap_or_default();
+        compared += 1;
+        if let Some(diff) = structural_diff(&expected, &actual) {
+            failures.push(format!("{program}/{name} {diff}"));
+        }
+    }
+    assert!(compared > 0, "no reference XML in {}", source_dir.display());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    // nothing lands in the working directory
+    let mut written: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    written.sort();
+    assert!(
+        written
+            .iter()
+            .all(|name| name.ends_with(".xml") && name != "tokens.xml" && name != "output.xml"),
+        "{written:?}"
+    );
+}
+
+#[test]
+fn array_test() {
+    check_program("ArrayTest");
+}
+
+#[test]
+fn square() {
+    check_program("Square");
+}
+
+#[test]
+fn expression_less_square() {
+    check_program("ExpressionLessSquare");
+}
+
+#[test]
+fn diff_names_the_enclosing_elements() {
+    let expected = "<class>\n  <keyword> class </keyword>\n  <symbol> &lt; </symbol>\n</class>\n";
+    let actual = "<class><keyword>class</keyword><symbol> &gt; </symbol></class>";
+    let diff = structural_diff(expected, actual).unwrap();
+    assert!(diff.starts_with("in class:"), "{diff}");
+    assert!(
+        diff.contains("expected <symbol> &lt; </symbol> (line 3)"),
+        "{diff}"
+    );
+    assert!(
+        structural_diff(expected, &expected.replace("  ", "")).is_none(),
+        "whitespace should not matter"
+    );
+}
+
+#[test]
+fn reference_xml_is_not_overwritten() {
+    let dir = scratch_dir("keep-reference");
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("../ArrayTest/Main.jack");
+    fs::copy(source, dir.join("Main.jack")).unwrap();
+    fs::write(dir.join("Main.xml"), "reference").unwrap();
+    let analyze = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_compiler_p10"))
+            .args(args)
+            .arg(dir.join("Main.jack"))
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+
+    let output = analyze(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Main.xml already exists"), "{stderr}");
+    assert_eq!(
+        fs::read_to_string(dir.join("Main.xml")).unwrap(),
+        "reference"
+    );
+    assert!(!dir.join("MainT.xml").exists());
+
+    let output = analyze(&["--overwrite"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(
+        fs::read_to_string(dir.join("Main.xml"))
+            .unwrap()
+            .starts_with("<class>")
+    );
+}
@@ -0,0 +1,90 @@
+//! Compares the course's token and parse-tree XML by structure, for the conformance
+//! tests of this analyzer and of the project 11 compiler.
+
+/// One piece of the course's XML: `<tag>`, `</tag>`, or `<tag> text </tag>` on one element.
+#[derive(Debug, PartialEq)]
+enum XmlEvent {
+    Open(String),
+    Close(String),
+    Leaf(String, String),
+}
+
+impl XmlEvent {
+    fn show(&self) -> String {
+        match self {
+            XmlEvent::Open(tag) => format!("<{tag}>"),
+            XmlEvent::Close(tag) => format!("</{tag}>"),
+            XmlEvent::Leaf(tag, text) => format!("<{tag}> {text} </{tag}>"),
+        }
+    }
+}
+
+/// Reads the elements of `xml` with their line numbers. Whitespace between and around
+/// elements is ignored, as is the whitespace padding the text of a leaf.
+fn parse_xml(xml: &str) -> Vec<(usize, XmlEvent)> {
+    let line_of = |offset: usize| xml[..offset].matches('\n').count() + 1;
+    let mut events = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        let offset = xml.len() - rest.len() + start;
+        let end = rest[start..].find('>').expect("unclosed tag") + start;
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(tag) = tag.strip_prefix('/') {
+            events.push((line_of(offset), XmlEvent::Close(tag.to_string())));
+            continue;
+        }
+        // an element whose only content is text, e.g. `<symbol> { </symbol>`
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        let close = format!("</{tag}>");
+        if rest[text_end..].starts_with(&close) {
+            let text = rest[..text_end].trim().to_string();
+            events.push((line_of(offset), XmlEvent::Leaf(tag.to_string(), text)));
+            rest = &rest[text_end + close.len()..];
+        } else {
+            events.push((line_of(offset), XmlEvent::Open(tag.to_string())));
+        }
+    }
+    events
+}
+
+/// Describes where `actual` first departs from `expected`: the enclosing elements and the
+/// line in each file. `None` if they match.
+pub fn structural_diff(expected: &str, actual: &str) -> Option<String> {
+    let expected = parse_xml(expected);
+    let actual = parse_xml(actual);
+    let mut path: Vec<&str> = Vec::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(i), actual.get(i));
+        if let (Some((_, e)), Some((_, a))) = (e, a)
+            && e == a
+        {
+            match e {
+                XmlEvent::Open(tag) => path.push(tag),
+                XmlEvent::Close(_) => {
+                    path.pop();
+                }
+                XmlEvent::Leaf(..) => {}
+            }
+            continue;
+        }
+
+        let describe = |event: Option<&(usize, XmlEvent)>| match event {
+            Some((line, event)) => format!("{} (line {line})", event.show()),
+            None => "end of file".to_string(),
+        };
+        return Some(format!(
+            "in {}:\n  expected {}\n     found {}",
+            if path.is_empty() {
+                "the document".to_string()
+            } else {
+                path.join(" > ")
+            },
+            describe(e),
+            describe(a)
+        ));
+    }
+    None
+}
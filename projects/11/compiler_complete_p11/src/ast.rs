@@ -0,0 +1,291 @@
+// Typed syntax tree produced by compilation_engine. Every node keeps the position of the
+// token it starts at so later passes can point back into the source.
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Class {
+    pub name: String,
+    pub var_decs: Vec<ClassVarDec>,
+    // `--extensions` constants and enums, which take no memory
+    pub constants: Vec<ConstDec>,
+    pub enums: Vec<EnumDec>,
+    pub subroutines: Vec<SubroutineDec>,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassVarKind {
+    Static,
+    Field,
+}
+
+impl ClassVarKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClassVarKind::Static => "static",
+            ClassVarKind::Field => "field",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ClassVarDec {
+    pub kind: ClassVarKind,
+    pub type_name: String,
+    pub names: Vec<(String, Span)>,
+    pub span: Span,
+}
+
+/// `const type name = expression;`, whose value must be known at compile time.
+#[derive(Clone, Debug)]
+pub struct ConstDec {
+    pub type_name: String,
+    pub name: String,
+    pub value: Expression,
+    pub span: Span,
+}
+
+/// `enum Name { A, B = 5, C }`: `int` constants counting up from 0, or from the last
+/// member given a value.
+#[derive(Clone, Debug)]
+pub struct EnumDec {
+    pub name: String,
+    pub members: Vec<EnumMember>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnumMember {
+    pub name: String,
+    pub value: Option<Expression>,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubroutineKind {
+    Constructor,
+    Function,
+    Method,
+}
+
+impl SubroutineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubroutineKind::Constructor => "constructor",
+            SubroutineKind::Function => "function",
+            SubroutineKind::Method => "method",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    pub type_name: String,
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct VarDec {
+    pub type_name: String,
+    pub names: Vec<(String, Span)>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct SubroutineDec {
+    pub kind: SubroutineKind,
+    pub return_type: String,
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub var_decs: Vec<VarDec>,
+    pub statements: Vec<Statement>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum StatementKind {
+    Let {
+        name: String,
+        index: Option<Expression>,
+        value: Expression,
+    },
+    If {
+        condition: Expression,
+        then_branch: Vec<Statement>,
+        else_branch: Option<Vec<Statement>>,
+    },
+    While {
+        condition: Expression,
+        body: Vec<Statement>,
+    },
+    /// `for (init; condition; step) { body }`, an `--extensions` statement. `init` and
+    /// `step` are `let` or `do` statements.
+    For {
+        init: Box<Statement>,
+        condition: Expression,
+        step: Box<Statement>,
+        body: Vec<Statement>,
+    },
+    Do(SubroutineCall),
+    Return(Option<Expression>),
+    // leave or restart the innermost loop (`--extensions`)
+    Break,
+    Continue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    And,
+    Or,
+    Lt,
+    Gt,
+    Eq,
+}
+
+impl BinaryOp {
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "+" => Some(BinaryOp::Add),
+            "-" => Some(BinaryOp::Sub),
+            "*" => Some(BinaryOp::Mul),
+            "/" => Some(BinaryOp::Div),
+            "&" => Some(BinaryOp::And),
+            "|" => Some(BinaryOp::Or),
+            "<" => Some(BinaryOp::Lt),
+            ">" => Some(BinaryOp::Gt),
+            "=" => Some(BinaryOp::Eq),
+            _ => None,
+        }
+    }
+
+    /// How tightly the operator binds with `--precedence`; higher binds tighter. Standard
+    /// Jack ignores this and applies operators left to right.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Mul | BinaryOp::Div => 4,
+            BinaryOp::Add | BinaryOp::Sub => 3,
+            BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Eq => 2,
+            BinaryOp::And | BinaryOp::Or => 1,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::And => "&",
+            BinaryOp::Or => "|",
+            BinaryOp::Lt => "<",
+            BinaryOp::Gt => ">",
+            BinaryOp::Eq => "=",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+impl UnaryOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::Neg => "-",
+            UnaryOp::Not => "~",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeywordConstant {
+    True,
+    False,
+    Null,
+    This,
+}
+
+impl KeywordConstant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeywordConstant::True => "true",
+            KeywordConstant::False => "false",
+            KeywordConstant::Null => "null",
+            KeywordConstant::This => "this",
+        }
+    }
+}
+
+/// `term (op term)*` as a tree. Jack evaluates operators left to right, so a chain parses
+/// into a left-leaning tree unless `--precedence` is on; either way an in-order walk gives
+/// back the source order.
+#[derive(Clone, Debug)]
+pub enum Expression {
+    Term(Term),
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+        span: Span,
+    },
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Term(term) => term.span,
+            Expression::Binary { lhs, .. } => lhs.span(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Term {
+    pub kind: TermKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum TermKind {
+    IntegerConstant(u16),
+    StringConstant(String),
+    KeywordConstant(KeywordConstant),
+    VarName(String),
+    ArrayAccess {
+        name: String,
+        index: Box<Expression>,
+    },
+    Call(SubroutineCall),
+    Paren(Box<Expression>),
+    Unary {
+        op: UnaryOp,
+        term: Box<Term>,
+    },
+}
+
+/// `name(args)` or `receiver.name(args)`, where the receiver is a variable or a class name.
+#[derive(Clone, Debug)]
+pub struct SubroutineCall {
+    pub receiver: Option<String>,
+    pub name: String,
+    pub args: Vec<Expression>,
+    pub span: Span,
+}
@@ -0,0 +1,50 @@
+use std::{env, io, process};
+
+use compiler_complete_p11::lsp::{Server, parse_error, read_message, write_message};
+
+fn main() -> io::Result<()> {
+    let mut extensions = false;
+    let mut precedence = false;
+    let mut typecheck = false;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--extensions" => extensions = true,
+            "--precedence" => precedence = true,
+            "--typecheck" => typecheck = true,
+            // editors commonly pass this to say the server talks over stdin and stdout
+            "--stdio" => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "unknown option {}\nUsage: jack_lsp [--extensions] [--precedence] [--typecheck]",
+                        arg
+                    ),
+                ));
+            }
+        }
+    }
+
+    // the editor starts the server and talks to it over stdin and stdout
+    let mut server = Server::new(extensions, precedence, typecheck);
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(()),
+            // one bad message does not end the session
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                write_message(&mut output, &parse_error(&err))?;
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        for reply in server.handle(&message) {
+            write_message(&mut output, &reply)?;
+        }
+        if let Some(code) = server.exit_code() {
+            process::exit(code);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::{env, io, process};
+
+use compiler_complete_p11::diagnostic;
+use compiler_complete_p11::formatter::format_source;
+
+fn usage_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_string())
+}
+
+/// The `.jack` files named on the command line, with folders expanded to the files in them.
+fn jack_files(inputs: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let mut in_dir = Vec::new();
+            for entry in fs::read_dir(input)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "jack") {
+                    in_dir.push(path);
+                }
+            }
+            in_dir.sort();
+            files.extend(in_dir);
+        } else {
+            files.push(input.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Formats one file: rewrites it, or with `check` only reports it. Returns whether the
+/// file was already formatted.
+fn format_file(path: &Path, check: bool, extensions: bool) -> io::Result<bool> {
+    let source = fs::read_to_string(path)?;
+    let formatted = format_source(&source, extensions)?;
+    if formatted == source {
+        return Ok(true);
+    }
+    if check {
+        println!("{} is not formatted", path.display());
+    } else {
+        fs::write(path, formatted)?;
+    }
+    Ok(false)
+}
+
+fn main() {
+    // CI reads this line; the files themselves are already reported
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run() -> io::Result<()> {
+    let mut check = false;
+    let mut extensions = false;
+    let mut inputs = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--extensions" => extensions = true,
+            _ if arg.starts_with('-') => {
+                return Err(usage_error(&format!("unknown option {}", arg)));
+            }
+            _ => inputs.push(PathBuf::from(arg)),
+        }
+    }
+    if inputs.is_empty() {
+        return Err(usage_error(
+            "Usage: jackfmt [--check] [--extensions] FILE.jack|DIR...",
+        ));
+    }
+
+    // with --check nothing is written; the exit status says whether everything is formatted
+    let mut unformatted = 0;
+    let mut failed = 0;
+    for path in jack_files(&inputs)? {
+        match format_file(&path, check, extensions) {
+            Ok(true) => {}
+            Ok(false) => unformatted += 1,
+            Err(err) => {
+                failed += 1;
+                let source = fs::read_to_string(&path).unwrap_or_default();
+                match diagnostic::from_io_error(&err) {
+                    Some(error) => {
+                        eprint!("{}", error.render(&path.display().to_string(), &source))
+                    }
+                    None => eprintln!("{}: {}", path.display(), err),
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} file(s) could not be formatted", failed),
+        ));
+    }
+    if check && unformatted > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} file(s) are not formatted", unformatted),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use crate::ast::{Class, SubroutineCall, SubroutineKind};
+use crate::symbol_table::symbol_table;
+
+/// Description of the Jack OS used when no other one is given.
+pub const OS_API: &str = include_str!("os_api.txt");
+
+#[derive(Clone, Debug)]
+pub struct SubroutineInfo {
+    pub kind: SubroutineKind,
+    pub return_type: String,
+    // (type, name) of each parameter
+    pub parameters: Vec<(String, String)>,
+}
+
+impl SubroutineInfo {
+    /// The subroutine's declaration as written in the OS API description, e.g.
+    /// `function int Math.max(int a, int b)`.
+    pub fn signature(&self, class_name: &str, name: &str) -> String {
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|(type_name, name)| format!("{} {}", type_name, name))
+            .collect();
+        format!(
+            "{} {} {}.{}({})",
+            self.kind.as_str(),
+            self.return_type,
+            class_name,
+            name,
+            parameters.join(", ")
+        )
+    }
+}
+
+/// Every class the program can call into, with the kind and signature of its subroutines.
+/// Built from the OS API description and then every parsed class, so a class compiled from
+/// source replaces its OS entry.
+#[derive(Default)]
+pub struct ClassIndex {
+    classes: BTreeMap<String, BTreeMap<String, SubroutineInfo>>,
+}
+
+/// Where a call goes once the receiver has been looked up.
+pub struct CallTarget<'a> {
+    pub class_name: String,
+    // whether an object reference is passed as argument 0
+    pub on_object: bool,
+    // `None` if the class is not indexed or has no such subroutine
+    pub subroutine: Option<&'a SubroutineInfo>,
+}
+
+fn api_error(line_number: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("OS API line {}: {}", line_number, message),
+    )
+}
+
+impl ClassIndex {
+    pub fn new() -> Self {
+        Self {
+            classes: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_class(&mut self, class: &Class) {
+        let subroutines = class
+            .subroutines
+            .iter()
+            .map(|s| {
+                let info = SubroutineInfo {
+                    kind: s.kind,
+                    return_type: s.return_type.clone(),
+                    parameters: s
+                        .parameters
+                        .iter()
+                        .map(|p| (p.type_name.clone(), p.name.clone()))
+                        .collect(),
+                };
+                (s.name.clone(), info)
+            })
+            .collect();
+        self.classes.insert(class.name.clone(), subroutines);
+    }
+
+    /// Adds the subroutines listed in an API description: one
+    /// `kind returnType Class.name(type name, ...)` per line, `//` starts a comment.
+    pub fn add_api(&mut self, description: &str) -> io::Result<()> {
+        for (i, line) in description.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split("//").next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (signature, rest) = line
+                .split_once('(')
+                .ok_or_else(|| api_error(line_number, "missing '('"))?;
+            let parameter_list = rest
+                .strip_suffix(')')
+                .ok_or_else(|| api_error(line_number, "missing ')'"))?;
+
+            let words: Vec<&str> = signature.split_whitespace().collect();
+            let [kind, return_type, full_name] = words[..] else {
+                return Err(api_error(
+                    line_number,
+                    "expected 'kind returnType Class.name(...)'",
+                ));
+            };
+            let kind = match kind {
+                "constructor" => SubroutineKind::Constructor,
+                "function" => SubroutineKind::Function,
+                "method" => SubroutineKind::Method,
+                _ => return Err(api_error(line_number, "unknown subroutine kind")),
+            };
+            let (class_name, name) = full_name
+                .split_once('.')
+                .ok_or_else(|| api_error(line_number, "expected Class.name"))?;
+
+            let mut parameters = Vec::new();
+            for parameter in parameter_list.split(',').filter(|p| !p.trim().is_empty()) {
+                let words: Vec<&str> = parameter.split_whitespace().collect();
+                let [type_name, parameter_name] = words[..] else {
+                    return Err(api_error(line_number, "expected 'type name' parameter"));
+                };
+                parameters.push((type_name.to_string(), parameter_name.to_string()));
+            }
+
+            self.classes
+                .entry(class_name.to_string())
+                .or_default()
+                .insert(
+                    name.to_string(),
+                    SubroutineInfo {
+                        kind,
+                        return_type: return_type.to_string(),
+                        parameters,
+                    },
+                );
+        }
+        Ok(())
+    }
+
+    pub fn contains_class(&self, class_name: &str) -> bool {
+        self.classes.contains_key(class_name)
+    }
+
+    pub fn subroutine(&self, class_name: &str, name: &str) -> Option<&SubroutineInfo> {
+        self.classes.get(class_name)?.get(name)
+    }
+
+    /// The subroutines of `class_name` by name; none if the class is not indexed.
+    pub fn subroutines(&self, class_name: &str) -> impl Iterator<Item = (&str, &SubroutineInfo)> {
+        self.classes
+            .get(class_name)
+            .into_iter()
+            .flatten()
+            .map(|(name, info)| (name.as_str(), info))
+    }
+
+    /// Resolves `call` made from inside `class_name`. A receiver that is a variable in
+    /// scope means a method call on that object; otherwise the receiver is a class name.
+    pub fn resolve_call(
+        &self,
+        class_name: &str,
+        symbol_table: &symbol_table,
+        call: &SubroutineCall,
+    ) -> CallTarget<'_> {
+        let (target_class, on_object) = match &call.receiver {
+            None => (class_name.to_string(), true),
+            Some(receiver) => match symbol_table.type_of(receiver) {
+                Some(type_name) => (type_name.clone(), true),
+                None => (receiver.clone(), false),
+            },
+        };
+
+        CallTarget {
+            subroutine: self.subroutine(&target_class, &call.name),
+            class_name: target_class,
+            on_object,
+        }
+    }
+}
@@ -0,0 +1,655 @@
+use std::io;
+
+use crate::ast::{
+    BinaryOp, Class, Expression, KeywordConstant, Statement, StatementKind, SubroutineCall,
+    SubroutineDec, SubroutineKind, Term, TermKind, UnaryOp,
+};
+use crate::class_index::ClassIndex;
+use crate::fold::{
+    constant_value, constant_value_with, define_constants, has_side_effects, is_boolean_valued,
+};
+use crate::symbol_table::{Identifier_Kind, IdentifierEntry, symbol_table};
+use crate::tokenizer::hack_char_code;
+use crate::vm_writer::VM_Writer;
+
+/// Walks a parsed class and emits its VM code through `VM_Writer`.
+pub struct CodeGenerator<'a> {
+    index: &'a ClassIndex,
+    symbol_table: symbol_table,
+    vm_writer: VM_Writer,
+    // numbers the labels of each `if`, `while` and `for` in the current subroutine
+    if_index: usize,
+    while_index: usize,
+    for_index: usize,
+    // the loops around the statement being compiled, innermost last
+    loops: Vec<LoopLabels>,
+    class_name: String,
+    // fold constant expressions, turn some multiplications into additions and branch
+    // without `not` where possible
+    optimize: bool,
+}
+
+/// Where `continue` and `break` jump to in one loop, and whether either was used, since
+/// some loop layouts only need those labels when they are.
+struct LoopLabels {
+    continue_label: String,
+    break_label: String,
+    continued: bool,
+    broken: bool,
+}
+
+/// A way to jump when a condition is false without computing the condition and `not`.
+enum Negation<'e> {
+    // jump when this is not zero
+    NonZero(&'e Expression),
+    NonZeroTerm(&'e Term),
+    // jump when `expression command constant` holds
+    Compare(&'e Expression, &'static str, i16),
+}
+
+/// Finds a cheaper test for "`condition` is false". The reference compiler's test is
+/// `condition; not; if-goto`, so a condition counts as true only when it is -1; every case
+/// here keeps that meaning for any value.
+fn negation(condition: &Expression) -> Option<Negation<'_>> {
+    match condition {
+        Expression::Term(term) => match &term.kind {
+            TermKind::Paren(expression) => negation(expression),
+            // ~e is -1 exactly when e is 0
+            TermKind::Unary {
+                op: UnaryOp::Not,
+                term,
+            } => Some(Negation::NonZeroTerm(term)),
+            _ => None,
+        },
+        Expression::Binary { op, lhs, rhs, .. } => {
+            match (op, constant_value(lhs), constant_value(rhs)) {
+                (BinaryOp::Eq, _, Some(0)) => Some(Negation::NonZero(lhs)),
+                (BinaryOp::Eq, Some(0), _) => Some(Negation::NonZero(rhs)),
+                // not (e < k) is e > k - 1, not (e > k) is e < k + 1
+                (BinaryOp::Lt, _, Some(k)) if k > i16::MIN => {
+                    Some(Negation::Compare(lhs, "gt", k - 1))
+                }
+                (BinaryOp::Gt, _, Some(k)) if k < i16::MAX => {
+                    Some(Negation::Compare(lhs, "lt", k + 1))
+                }
+                // k < e is e > k, k > e is e < k
+                (BinaryOp::Lt, Some(k), _) if k < i16::MAX => {
+                    Some(Negation::Compare(rhs, "lt", k + 1))
+                }
+                (BinaryOp::Gt, Some(k), _) if k > i16::MIN => {
+                    Some(Negation::Compare(rhs, "gt", k - 1))
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Multiplying by these is cheaper as a short run of `add`s than a `Math.multiply` call.
+fn is_cheap_factor(factor: i16) -> bool {
+    let magnitude = factor.unsigned_abs();
+    magnitude < 16 || magnitude.is_power_of_two()
+}
+
+impl<'a> CodeGenerator<'a> {
+    pub fn new(vm_writer: VM_Writer, index: &'a ClassIndex) -> Self {
+        Self {
+            index,
+            symbol_table: symbol_table::new(),
+            vm_writer,
+            if_index: 0,
+            while_index: 0,
+            for_index: 0,
+            loops: Vec::new(),
+            class_name: String::new(),
+            optimize: true,
+        }
+    }
+
+    pub fn with_optimizations(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    pub fn compile_class(&mut self, class: &Class) -> io::Result<()> {
+        self.class_name = class.name.clone();
+
+        for var_dec in &class.var_decs {
+            for (name, _) in &var_dec.names {
+                self.symbol_table
+                    .define(name, &var_dec.type_name, var_dec.kind.as_str());
+            }
+        }
+        if let Some(error) = define_constants(&mut self.symbol_table, class).first() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                error.message.clone(),
+            ));
+        }
+        for subroutine in &class.subroutines {
+            self.compile_subroutine(subroutine)?;
+        }
+
+        Ok(())
+    }
+
+    /// The writer, holding the VM code of the classes compiled so far.
+    pub fn into_vm_writer(self) -> VM_Writer {
+        self.vm_writer
+    }
+
+    fn compile_subroutine(&mut self, subroutine: &SubroutineDec) -> io::Result<()> {
+        self.symbol_table.start_subroutine();
+        // labels are local to a function in the VM language
+        self.if_index = 0;
+        self.while_index = 0;
+        self.for_index = 0;
+        if subroutine.kind == SubroutineKind::Method {
+            let class_name = self.class_name.clone();
+            self.symbol_table.define("this", &class_name, "argument");
+        }
+        for parameter in &subroutine.parameters {
+            self.symbol_table
+                .define(&parameter.name, &parameter.type_name, "argument");
+        }
+        let mut num_of_vars = 0;
+        for var_dec in &subroutine.var_decs {
+            for (name, _) in &var_dec.names {
+                self.symbol_table.define(name, &var_dec.type_name, "var");
+                num_of_vars += 1;
+            }
+        }
+
+        let full_f_name = format!("{}.{}", self.class_name, subroutine.name);
+        self.vm_writer.set_source_line(subroutine.span.line);
+        self.vm_writer.write_debug_function(&full_f_name)?;
+        for entry in &self.symbol_table.subroutine_scope {
+            self.vm_writer.write_variable(
+                entry.kind.kind_to_segment(),
+                entry.index,
+                &entry.type_name,
+                &entry.name,
+            )?;
+        }
+        self.vm_writer.write_function(&full_f_name, num_of_vars)?;
+        match subroutine.kind {
+            SubroutineKind::Constructor => {
+                // memory alloc allocates amount of vars so get num of fields for class
+                self.vm_writer
+                    .write_push("constant", self.symbol_table.field_index)?;
+                self.vm_writer.write_call("Memory.alloc", 1)?;
+                self.vm_writer.write_pop("pointer", 0)?;
+            }
+            SubroutineKind::Method => {
+                // arg + 0 holds the object ref, pop pointer 0 will store it in THIS register
+                self.vm_writer.write_push("argument", 0)?;
+                self.vm_writer.write_pop("pointer", 0)?;
+            }
+            SubroutineKind::Function => {}
+        }
+
+        self.compile_statements(&subroutine.statements)
+    }
+
+    /// The value of `expression` if it is known at compile time, including the class's
+    /// constants.
+    fn constant(&self, expression: &Expression) -> Option<i16> {
+        constant_value_with(expression, &|name| self.symbol_table.constant_value(name))
+    }
+
+    fn get_variable_from_scope(&self, var_to_look_for: &str) -> io::Result<&IdentifierEntry> {
+        let variable = self
+            .symbol_table
+            .subroutine_scope
+            .iter()
+            .find(|entry| entry.name == var_to_look_for)
+            .or_else(|| {
+                self.symbol_table
+                    .class_scope
+                    .iter()
+                    .find(|entry| entry.name == var_to_look_for)
+            });
+
+        variable.ok_or(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Something went wrong. Variable no found in either method or class scopes. Var that we looked for is: {}", var_to_look_for),
+        ))
+    }
+
+    fn push_variable(&mut self, name: &str) -> io::Result<()> {
+        let entry = self.get_variable_from_scope(name)?;
+        if let Identifier_Kind::CONST(value) = entry.kind {
+            return self.push_constant(value);
+        }
+        let segment = entry.kind.kind_to_segment();
+        let index = entry.index;
+        self.vm_writer.write_push(segment, index)
+    }
+
+    /// Pushes the address of `name[index]`. Nothing is kept outside the stack, so `index`
+    /// may itself contain array accesses and calls.
+    fn compile_array_address(&mut self, name: &str, index: &Expression) -> io::Result<()> {
+        self.push_variable(name)?;
+        self.compile_expression(index)?;
+        self.vm_writer.write_arithmetic("add")
+    }
+
+    fn compile_statements(&mut self, statements: &[Statement]) -> io::Result<()> {
+        for statement in statements {
+            self.compile_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    fn compile_statement(&mut self, statement: &Statement) -> io::Result<()> {
+        // what a statement writes after a nested body, e.g. a loop's `goto`, is still its own
+        let outer_line = self.vm_writer.source_line();
+        self.vm_writer.set_source_line(statement.span.line);
+        let result = self.compile_statement_kind(statement);
+        self.vm_writer.set_source_line(outer_line);
+        result
+    }
+
+    fn compile_statement_kind(&mut self, statement: &Statement) -> io::Result<()> {
+        match &statement.kind {
+            StatementKind::Let { name, index, value } => self.compile_let(name, index, value),
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.compile_if(condition, then_branch, else_branch.as_deref()),
+            StatementKind::While { condition, body } => self.compile_loop(condition, body, None),
+            StatementKind::For {
+                init,
+                condition,
+                step,
+                body,
+            } => {
+                self.compile_statement(init)?;
+                self.compile_loop(condition, body, Some(step))
+            }
+            StatementKind::Do(call) => {
+                self.compile_subroutine_call(call)?;
+                self.vm_writer.write_pop("temp", 0)
+            }
+            StatementKind::Return(value) => {
+                if let Some(value) = value {
+                    self.compile_expression(value)?;
+                }
+                self.vm_writer.write_return(value.is_some())
+            }
+            StatementKind::Break | StatementKind::Continue => {
+                let is_break = matches!(statement.kind, StatementKind::Break);
+                let labels = self.loops.last_mut().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "'break' or 'continue' outside of a loop",
+                    )
+                })?;
+                let label = if is_break {
+                    labels.broken = true;
+                    labels.break_label.clone()
+                } else {
+                    labels.continued = true;
+                    labels.continue_label.clone()
+                };
+                self.vm_writer.write_goto(&label)
+            }
+        }
+    }
+
+    fn compile_let(
+        &mut self,
+        name: &str,
+        index: &Option<Expression>,
+        value: &Expression,
+    ) -> io::Result<()> {
+        match index {
+            Some(index) => {
+                // The target address stays on the stack while the right-hand side runs,
+                // since any array access in it (or in a call it makes) moves pointer 1.
+                // Only once the value is known is it parked in temp 0 so THAT can be set.
+                self.compile_array_address(name, index)?;
+                self.compile_expression(value)?;
+                self.vm_writer.write_pop("temp", 0)?;
+                self.vm_writer.write_pop("pointer", 1)?;
+                self.vm_writer.write_push("temp", 0)?;
+                self.vm_writer.write_pop("that", 0)
+            }
+            None => {
+                self.compile_expression(value)?;
+                let entry = self.get_variable_from_scope(name)?;
+                let segment = entry.kind.kind_to_segment();
+                let index = entry.index;
+                self.vm_writer.write_pop(segment, index)
+            }
+        }
+    }
+
+    /// Jumps to `label` unless `condition` is true.
+    fn compile_jump_unless(&mut self, condition: &Expression, label: &str) -> io::Result<()> {
+        match negation(condition).filter(|_| self.optimize) {
+            Some(Negation::NonZero(expression)) => self.compile_expression(expression)?,
+            Some(Negation::NonZeroTerm(term)) => self.compile_term(term)?,
+            Some(Negation::Compare(expression, command, constant)) => {
+                self.compile_expression(expression)?;
+                self.push_constant(constant)?;
+                self.vm_writer.write_arithmetic(command)?;
+            }
+            None => {
+                self.compile_expression(condition)?;
+                self.vm_writer.write_arithmetic("not")?;
+            }
+        }
+        self.vm_writer.write_if(label)
+    }
+
+    /// Whether jumping when `condition` is true is cheaper than jumping when it is false.
+    /// Only a condition that is always -1 or 0 can be tested with a bare `if-goto`.
+    fn prefers_jump_if(&self, condition: &Expression) -> bool {
+        self.optimize && negation(condition).is_none() && is_boolean_valued(condition)
+    }
+
+    fn compile_if(
+        &mut self,
+        condition: &Expression,
+        then_branch: &[Statement],
+        else_branch: Option<&[Statement]>,
+    ) -> io::Result<()> {
+        if self.optimize
+            && let Some(value) = self.constant(condition)
+        {
+            return match (value == -1, else_branch) {
+                (true, _) => self.compile_statements(then_branch),
+                (false, Some(else_branch)) => self.compile_statements(else_branch),
+                (false, None) => Ok(()),
+            };
+        }
+
+        let label_idx = self.if_index;
+        self.if_index += 1;
+        let if_true_label = format!("IF_TRUE{}", label_idx);
+        let if_false_label = format!("IF_FALSE{}", label_idx);
+        let if_end_label = format!("IF_END{}", label_idx);
+
+        match else_branch {
+            None if self.optimize => {
+                self.compile_jump_unless(condition, &if_false_label)?;
+                self.compile_statements(then_branch)?;
+                self.vm_writer.write_label(&if_false_label)
+            }
+            Some(else_branch) if self.prefers_jump_if(condition) => {
+                self.compile_expression(condition)?;
+                self.vm_writer.write_if(&if_true_label)?;
+                self.compile_statements(else_branch)?;
+                self.vm_writer.write_goto(&if_end_label)?;
+                self.vm_writer.write_label(&if_true_label)?;
+                self.compile_statements(then_branch)?;
+                self.vm_writer.write_label(&if_end_label)
+            }
+            _ => {
+                self.compile_jump_unless(condition, &if_false_label)?;
+                self.compile_statements(then_branch)?;
+                self.vm_writer.write_goto(&if_end_label)?;
+                self.vm_writer.write_label(&if_false_label)?;
+                if let Some(else_branch) = else_branch {
+                    self.compile_statements(else_branch)?;
+                }
+                self.vm_writer.write_label(&if_end_label)
+            }
+        }
+    }
+
+    /// A `while` loop, or the loop of a `for` after its `init` when `step` is given.
+    fn compile_loop(
+        &mut self,
+        condition: &Expression,
+        body: &[Statement],
+        step: Option<&Statement>,
+    ) -> io::Result<()> {
+        let constant = self.constant(condition).filter(|_| self.optimize);
+        if constant.is_some_and(|value| value != -1) {
+            return Ok(());
+        }
+
+        let (prefix, label_idx) = match step {
+            None => ("WHILE", &mut self.while_index),
+            Some(_) => ("FOR", &mut self.for_index),
+        };
+        let label_idx = std::mem::replace(label_idx, *label_idx + 1);
+        let exp_label = format!("{}_EXP{}", prefix, label_idx);
+        let end_label = format!("{}_END{}", prefix, label_idx);
+        let loop_label = format!("{}_LOOP{}", prefix, label_idx);
+        let step_label = format!("{}_STEP{}", prefix, label_idx);
+        self.loops.push(LoopLabels {
+            continue_label: if step.is_some() {
+                step_label.clone()
+            } else {
+                exp_label.clone()
+            },
+            break_label: end_label.clone(),
+            continued: false,
+            broken: false,
+        });
+
+        let needs_end_label = if constant.is_some() {
+            // while (true)
+            self.vm_writer.write_label(&exp_label)?;
+            self.compile_loop_body(body, step, &step_label)?;
+            self.vm_writer.write_goto(&exp_label)?;
+            false
+        } else if self.prefers_jump_if(condition) {
+            // test at the bottom, so each pass takes one jump and needs no `not`
+            self.vm_writer.write_goto(&exp_label)?;
+            self.vm_writer.write_label(&loop_label)?;
+            self.compile_loop_body(body, step, &step_label)?;
+            self.vm_writer.write_label(&exp_label)?;
+            self.compile_expression(condition)?;
+            self.vm_writer.write_if(&loop_label)?;
+            false
+        } else {
+            self.vm_writer.write_label(&exp_label)?;
+            self.compile_jump_unless(condition, &end_label)?;
+            self.compile_loop_body(body, step, &step_label)?;
+            self.vm_writer.write_goto(&exp_label)?;
+            true
+        };
+
+        let labels = self.loops.pop().expect("pushed above");
+        if needs_end_label || labels.broken {
+            self.vm_writer.write_label(&end_label)?;
+        }
+        Ok(())
+    }
+
+    /// The body of a loop, then the step of a `for`, which `continue` jumps to.
+    fn compile_loop_body(
+        &mut self,
+        body: &[Statement],
+        step: Option<&Statement>,
+        step_label: &str,
+    ) -> io::Result<()> {
+        self.compile_statements(body)?;
+        if let Some(step) = step {
+            if self.loops.last().is_some_and(|labels| labels.continued) {
+                self.vm_writer.write_label(step_label)?;
+            }
+            self.compile_statement(step)?;
+        }
+        Ok(())
+    }
+
+    fn compile_expression(&mut self, expression: &Expression) -> io::Result<()> {
+        if self.optimize
+            && let Some(value) = self.constant(expression)
+        {
+            return self.push_constant(value);
+        }
+
+        match expression {
+            Expression::Term(term) => self.compile_term(term),
+            Expression::Binary { op, lhs, rhs, .. } => {
+                if self.optimize {
+                    match (op, self.constant(lhs), self.constant(rhs)) {
+                        (BinaryOp::Mul, _, Some(factor)) if is_cheap_factor(factor) => {
+                            return self.compile_multiply_by(lhs, factor);
+                        }
+                        (BinaryOp::Mul, Some(factor), _) if is_cheap_factor(factor) => {
+                            return self.compile_multiply_by(rhs, factor);
+                        }
+                        // no `/ 2^k` as a shift: the VM has none, and `-7 / 4` truncates to -1, not -2
+                        (BinaryOp::Div, _, Some(1)) => return self.compile_expression(lhs),
+                        (BinaryOp::Div, _, Some(-1)) => {
+                            self.compile_expression(lhs)?;
+                            return self.vm_writer.write_arithmetic("neg");
+                        }
+                        _ => {}
+                    }
+                }
+
+                self.compile_expression(lhs)?;
+                self.compile_expression(rhs)?;
+                match op {
+                    BinaryOp::Mul => self.vm_writer.write_call("Math.multiply", 2),
+                    BinaryOp::Div => self.vm_writer.write_call("Math.divide", 2),
+                    BinaryOp::Add => self.vm_writer.write_arithmetic("add"),
+                    BinaryOp::Sub => self.vm_writer.write_arithmetic("sub"),
+                    BinaryOp::And => self.vm_writer.write_arithmetic("and"),
+                    BinaryOp::Or => self.vm_writer.write_arithmetic("or"),
+                    BinaryOp::Lt => self.vm_writer.write_arithmetic("lt"),
+                    BinaryOp::Gt => self.vm_writer.write_arithmetic("gt"),
+                    BinaryOp::Eq => self.vm_writer.write_arithmetic("eq"),
+                }
+            }
+        }
+    }
+
+    /// Pushes any 16-bit value; `push constant` itself only takes 0..=32767.
+    fn push_constant(&mut self, value: i16) -> io::Result<()> {
+        if value >= 0 {
+            self.vm_writer.write_push("constant", value as usize)
+        } else if value == i16::MIN {
+            self.vm_writer.write_push("constant", i16::MAX as usize)?;
+            self.vm_writer.write_arithmetic("not")
+        } else {
+            self.vm_writer
+                .write_push("constant", value.unsigned_abs() as usize)?;
+            self.vm_writer.write_arithmetic("neg")
+        }
+    }
+
+    /// `expression * factor` without `Math.multiply`, which wraps around exactly like the
+    /// additions used here. `expression` is evaluated once even when `factor` is 0.
+    fn compile_multiply_by(&mut self, expression: &Expression, factor: i16) -> io::Result<()> {
+        if factor == 0 {
+            if has_side_effects(expression) {
+                self.compile_expression(expression)?;
+                self.vm_writer.write_pop("temp", 1)?;
+            }
+            return self.vm_writer.write_push("constant", 0);
+        }
+
+        self.compile_expression(expression)?;
+        let magnitude = factor.unsigned_abs();
+        if magnitude > 1 {
+            // double-and-add over the bits of the factor below the highest one: x stays
+            // in temp 1, the running product is doubled through temp 2
+            self.vm_writer.write_pop("temp", 1)?;
+            self.vm_writer.write_push("temp", 1)?;
+            for bit in (0..magnitude.ilog2()).rev() {
+                self.vm_writer.write_pop("temp", 2)?;
+                self.vm_writer.write_push("temp", 2)?;
+                self.vm_writer.write_push("temp", 2)?;
+                self.vm_writer.write_arithmetic("add")?;
+                if magnitude >> bit & 1 == 1 {
+                    self.vm_writer.write_push("temp", 1)?;
+                    self.vm_writer.write_arithmetic("add")?;
+                }
+            }
+        }
+        if factor < 0 {
+            self.vm_writer.write_arithmetic("neg")?;
+        }
+        Ok(())
+    }
+
+    fn compile_term(&mut self, term: &Term) -> io::Result<()> {
+        match &term.kind {
+            TermKind::IntegerConstant(value) => {
+                self.vm_writer.write_push("constant", *value as usize)
+            }
+            TermKind::StringConstant(value) => {
+                self.vm_writer
+                    .write_push("constant", value.chars().count())?;
+                self.vm_writer.write_call("String.new", 1)?;
+                for c in value.chars() {
+                    let code = hack_char_code(c).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("character {:?} is not in the Hack character set", c),
+                        )
+                    })?;
+                    self.vm_writer.write_push("constant", code as usize)?;
+                    self.vm_writer.write_call("String.appendChar", 2)?;
+                }
+                Ok(())
+            }
+            TermKind::KeywordConstant(constant) => match constant {
+                KeywordConstant::True => {
+                    self.vm_writer.write_push("constant", 1)?;
+                    self.vm_writer.write_arithmetic("neg")
+                }
+                KeywordConstant::False | KeywordConstant::Null => {
+                    self.vm_writer.write_push("constant", 0)
+                }
+                KeywordConstant::This => self.vm_writer.write_push("pointer", 0),
+            },
+            TermKind::VarName(name) => self.push_variable(name),
+            TermKind::ArrayAccess { name, index } => {
+                self.compile_array_address(name, index)?;
+                self.vm_writer.write_pop("pointer", 1)?;
+                self.vm_writer.write_push("that", 0)
+            }
+            TermKind::Call(call) => self.compile_subroutine_call(call),
+            TermKind::Paren(expression) => self.compile_expression(expression),
+            TermKind::Unary { op, term } => {
+                self.compile_term(term)?;
+                match op {
+                    UnaryOp::Neg => self.vm_writer.write_arithmetic("neg"),
+                    UnaryOp::Not => self.vm_writer.write_arithmetic("not"),
+                }
+            }
+        }
+    }
+
+    fn compile_subroutine_call(&mut self, call: &SubroutineCall) -> io::Result<()> {
+        let target = self
+            .index
+            .resolve_call(&self.class_name, &self.symbol_table, call);
+        // the index knows the real kind; without it, fall back to how the call is written
+        let is_method = match target.subroutine {
+            Some(subroutine) => subroutine.kind == SubroutineKind::Method,
+            None => target.on_object,
+        };
+
+        if is_method {
+            match &call.receiver {
+                Some(receiver) if target.on_object => self.push_variable(receiver)?,
+                // method on the current object
+                _ => self.vm_writer.write_push("pointer", 0)?,
+            }
+        }
+        self.compile_expression_list(&call.args)?;
+
+        let full_subroutine_name = format!("{}.{}", target.class_name, call.name);
+        let n_args = call.args.len() + usize::from(is_method);
+        self.vm_writer.write_call(&full_subroutine_name, n_args)
+    }
+
+    fn compile_expression_list(&mut self, args: &[Expression]) -> io::Result<()> {
+        for arg in args {
+            self.compile_expression(arg)?;
+        }
+        Ok(())
+    }
+}
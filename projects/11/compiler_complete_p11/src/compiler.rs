@@ -0,0 +1,196 @@
+use std::io;
+use std::path::Path;
+
+use crate::ast::{Class, Span};
+use crate::class_index::{ClassIndex, OS_API};
+use crate::code_generator::CodeGenerator;
+use crate::diagnostic::{self, Diagnostic, Severity};
+use crate::lints::{self, Lint};
+use crate::parser::compilation_engine;
+use crate::tokenizer::jack_tokenizer;
+use crate::vm_writer::VM_Writer;
+use crate::{semantic, type_checker};
+
+/// How to compile. The default is standard Jack, optimized, with every warning on.
+#[derive(Clone, Default)]
+pub struct CompileOptions {
+    // report likely type mistakes as warnings
+    pub typecheck: bool,
+    // give `* /` priority over `+ -`, then comparisons, then `& |`
+    pub precedence: bool,
+    // emit every operator and branch as written, like the reference compiler
+    pub no_optimize: bool,
+    // accept `for`, `break`/`continue`, `'c'`, `0x..`, `else if`, `const` and `enum`
+    pub extensions: bool,
+    // also produce the `Foo.vm.map` debug map of each class
+    pub debug: bool,
+    // warnings turned off with `--allow`
+    pub allowed: Vec<Lint>,
+    // OS API description to use instead of the built-in one
+    pub os_api: Option<String>,
+}
+
+/// A diagnostic with the name of the file it is about.
+pub type FileDiagnostic = (String, Diagnostic);
+
+/// One class of a project, compiled.
+pub struct CompiledClass {
+    // the name its source was given
+    pub file_name: String,
+    pub tokens: jack_tokenizer,
+    pub class: Class,
+    pub vm: String,
+    // the debug map, with `debug`
+    pub debug_map: Option<String>,
+}
+
+/// What compiling a project gave: every class if nothing was wrong, and every error and
+/// warning in the order they were found.
+pub struct Compilation {
+    pub classes: Vec<CompiledClass>,
+    pub diagnostics: Vec<FileDiagnostic>,
+}
+
+impl Compilation {
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|(_, d)| d.severity == Severity::Error)
+            .count()
+    }
+
+    fn report(&mut self, file_name: &str, diagnostics: Vec<Diagnostic>) {
+        self.diagnostics
+            .extend(diagnostics.into_iter().map(|d| (file_name.to_string(), d)));
+    }
+}
+
+/// The diagnostic an error from the tokenizer or code generator carries, or one made from
+/// its message.
+fn diagnostic_of(err: &io::Error, span: Span) -> Diagnostic {
+    diagnostic::from_io_error(err)
+        .cloned()
+        .unwrap_or_else(|| Diagnostic::error(span, err.to_string()))
+}
+
+/// Compiles the classes of one program, given as `(file name, source)` pairs, the way the
+/// compiler compiles a folder: every class is parsed and checked before any is compiled,
+/// so no VM code comes out of a program with errors. Nothing is read or written; the
+/// only error is an OS API description that does not parse.
+pub fn compile_project_with(
+    files: &[(&str, &str)],
+    options: &CompileOptions,
+) -> io::Result<Compilation> {
+    let mut index = ClassIndex::new();
+    index.add_api(options.os_api.as_deref().unwrap_or(OS_API))?;
+
+    let mut compilation = Compilation {
+        classes: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    let mut parsed = Vec::new();
+    for &(file_name, source) in files {
+        let tokens = match jack_tokenizer::new(source, options.extensions) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                compilation.report(file_name, vec![diagnostic_of(&err, Span::default())]);
+                continue;
+            }
+        };
+        let mut parser = compilation_engine::new(tokens.tokens.clone())
+            .with_precedence(options.precedence)
+            .with_extensions(options.extensions);
+        let class = parser.compile_class();
+        compilation.report(file_name, parser.take_warnings());
+        match class {
+            Ok(class) => parsed.push((file_name, tokens, class)),
+            Err(errors) => compilation.report(file_name, errors),
+        }
+    }
+    if compilation.error_count() > 0 {
+        return Ok(compilation);
+    }
+
+    for (_, _, class) in &parsed {
+        index.add_class(class);
+    }
+    for (file_name, _, class) in &parsed {
+        compilation.report(file_name, semantic::check(class, &index));
+    }
+    if compilation.error_count() > 0 {
+        return Ok(compilation);
+    }
+
+    // only a whole program shows which subroutines are never called
+    let classes: Vec<&Class> = parsed.iter().map(|(_, _, class)| class).collect();
+    let called = classes
+        .iter()
+        .any(|class| class.name == "Main")
+        .then(|| lints::called_subroutines(&classes, &index));
+    for (file_name, _, class) in &parsed {
+        compilation.report(
+            file_name,
+            lints::check(class, called.as_ref(), &options.allowed),
+        );
+    }
+    if options.typecheck {
+        for (file_name, _, class) in &parsed {
+            compilation.report(file_name, type_checker::check(class, &index));
+        }
+    }
+
+    for (file_name, tokens, class) in parsed {
+        let mut vm_writer = VM_Writer::new();
+        if options.debug {
+            // the map names the file, wherever it was
+            let source_name = Path::new(file_name)
+                .file_name()
+                .map_or(file_name.into(), |name| name.to_string_lossy());
+            vm_writer = vm_writer.with_debug_map(&format!("{}.vm", class.name), &source_name);
+        }
+        let mut generator =
+            CodeGenerator::new(vm_writer, &index).with_optimizations(!options.no_optimize);
+        if let Err(err) = generator.compile_class(&class) {
+            compilation.report(file_name, vec![diagnostic_of(&err, class.span)]);
+            continue;
+        }
+        let (vm, debug_map) = generator.into_vm_writer().into_output();
+        compilation.classes.push(CompiledClass {
+            file_name: file_name.to_string(),
+            tokens,
+            class,
+            vm,
+            debug_map,
+        });
+    }
+    Ok(compilation)
+}
+
+/// Like `compile_project_with`, with the default options: the `(file name, VM code)` of
+/// every class, or the errors that stopped the program from compiling.
+pub fn compile_project(
+    files: &[(&str, &str)],
+) -> Result<Vec<(String, String)>, Vec<FileDiagnostic>> {
+    let compilation = compile_project_with(files, &CompileOptions::default())
+        .expect("the built-in OS API description is valid");
+    if compilation.error_count() > 0 {
+        let errors = compilation.diagnostics.into_iter();
+        return Err(errors
+            .filter(|(_, d)| d.severity == Severity::Error)
+            .collect());
+    }
+    Ok(compilation
+        .classes
+        .into_iter()
+        .map(|compiled| (compiled.file_name, compiled.vm))
+        .collect())
+}
+
+/// Compiles a single class on its own: its VM code, or its errors. Calls to classes other
+/// than the OS are not checked.
+pub fn compile_class(source: &str) -> Result<String, Vec<Diagnostic>> {
+    match compile_project(&[("", source)]) {
+        Ok(mut classes) => Ok(classes.remove(0).1),
+        Err(errors) => Err(errors.into_iter().map(|(_, d)| d).collect()),
+    }
+}
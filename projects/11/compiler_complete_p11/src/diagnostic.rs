@@ -0,0 +1,149 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::ast::Span;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A message about one position in a source file, shown to the user with the offending line.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    // what the parser would have accepted here, e.g. `';'` or `identifier`
+    pub expected: Vec<String>,
+}
+
+/// How many characters of `line` from `column` on belong to the word there, at least one,
+/// so a symbol or the end of the line is still marked.
+pub fn token_length(line: &str, column: usize) -> usize {
+    line.chars()
+        .skip(column.saturating_sub(1))
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .count()
+        .max(1)
+}
+
+impl Diagnostic {
+    pub fn error(span: Span, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            span,
+            message,
+            expected: Vec::new(),
+        }
+    }
+
+    pub fn warning(span: Span, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(span, message)
+        }
+    }
+
+    pub fn with_expected(mut self, expected: &[&str]) -> Self {
+        self.expected = expected.iter().map(|e| e.to_string()).collect();
+        self
+    }
+
+    /// `expected 'a', 'b' or 'c'`, or `None` when nothing was expected.
+    pub fn expected_text(&self) -> Option<String> {
+        let (last, rest) = self.expected.split_last()?;
+        if rest.is_empty() {
+            Some(format!("expected {}", last))
+        } else {
+            Some(format!("expected {} or {}", rest.join(", "), last))
+        }
+    }
+
+    /// The full report: position, message, the source line with the offending token
+    /// underlined, and what was expected instead.
+    ///
+    /// ```text
+    /// Main.jack:3:19: error: unexpected symbol '='
+    ///    |
+    ///  3 |         let x = = 1;
+    ///    |                 ^
+    ///    = expected integer constant, string constant, ...
+    /// ```
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut out = format!(
+            "{}:{}:{}: {}: {}\n",
+            file_name,
+            self.span.line,
+            self.span.column,
+            self.severity.as_str(),
+            self.message
+        );
+
+        let gutter = " ".repeat(self.span.line.to_string().len());
+        if let Some(line) = source.lines().nth(self.span.line.wrapping_sub(1)) {
+            let line = line.trim_end();
+            // keep tabs so the caret lines up however the terminal renders them
+            let padding: String = line
+                .chars()
+                .take(self.span.column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let token_len = token_length(line, self.span.column);
+
+            out.push_str(&format!("{} |\n", gutter));
+            out.push_str(&format!("{} | {}\n", self.span.line, line));
+            out.push_str(&format!(
+                "{} | {}{}\n",
+                gutter,
+                padding,
+                "^".repeat(token_len)
+            ));
+        }
+        if let Some(expected) = self.expected_text() {
+            out.push_str(&format!("{} = {}\n", gutter, expected));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.span.line,
+            self.span.column,
+            self.severity.as_str(),
+            self.message
+        )?;
+        if let Some(expected) = self.expected_text() {
+            write!(f, "; {}", expected)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Diagnostic {}
+
+impl From<Diagnostic> for io::Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, diagnostic)
+    }
+}
+
+/// The diagnostic an `io::Error` was made from, if any.
+pub fn from_io_error(err: &io::Error) -> Option<&Diagnostic> {
+    err.get_ref()?.downcast_ref::<Diagnostic>()
+}
@@ -0,0 +1,160 @@
+use crate::ast::{BinaryOp, Class, Expression, KeywordConstant, Span, Term, TermKind, UnaryOp};
+use crate::diagnostic::Diagnostic;
+use crate::symbol_table::symbol_table;
+
+/// The value of `expression` if it is known at compile time, computed with the Hack
+/// machine's 16-bit wrap-around. Division is only folded when it cannot fail at run time,
+/// so `1 / 0` still reaches `Math.divide` and its error.
+pub fn constant_value(expression: &Expression) -> Option<i16> {
+    constant_value_with(expression, &|_| None)
+}
+
+/// Like `constant_value`, with `names` giving the value of the named constants.
+pub fn constant_value_with(
+    expression: &Expression,
+    names: &impl Fn(&str) -> Option<i16>,
+) -> Option<i16> {
+    match expression {
+        Expression::Term(term) => term_value(term, names),
+        Expression::Binary { op, lhs, rhs, .. } => {
+            let (x, y) = (
+                constant_value_with(lhs, names)?,
+                constant_value_with(rhs, names)?,
+            );
+            Some(match op {
+                BinaryOp::Add => x.wrapping_add(y),
+                BinaryOp::Sub => x.wrapping_sub(y),
+                BinaryOp::Mul => x.wrapping_mul(y),
+                BinaryOp::Div => x.checked_div(y)?,
+                BinaryOp::And => x & y,
+                BinaryOp::Or => x | y,
+                BinaryOp::Lt => -i16::from(x < y),
+                BinaryOp::Gt => -i16::from(x > y),
+                BinaryOp::Eq => -i16::from(x == y),
+            })
+        }
+    }
+}
+
+fn term_value(term: &Term, names: &impl Fn(&str) -> Option<i16>) -> Option<i16> {
+    match &term.kind {
+        TermKind::IntegerConstant(value) => i16::try_from(*value).ok(),
+        TermKind::KeywordConstant(KeywordConstant::True) => Some(-1),
+        TermKind::KeywordConstant(KeywordConstant::False | KeywordConstant::Null) => Some(0),
+        TermKind::VarName(name) => names(name),
+        TermKind::Paren(expression) => constant_value_with(expression, names),
+        TermKind::Unary { op, term } => {
+            let value = term_value(term, names)?;
+            Some(match op {
+                UnaryOp::Neg => value.wrapping_neg(),
+                UnaryOp::Not => !value,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Whether evaluating `expression` can do more than produce a value, i.e. it calls a
+/// subroutine. Such an expression must still be evaluated even if its value is not needed.
+pub fn has_side_effects(expression: &Expression) -> bool {
+    match expression {
+        Expression::Term(term) => term_has_side_effects(term),
+        Expression::Binary { lhs, rhs, .. } => has_side_effects(lhs) || has_side_effects(rhs),
+    }
+}
+
+fn term_has_side_effects(term: &Term) -> bool {
+    match &term.kind {
+        TermKind::Call(_) => true,
+        TermKind::ArrayAccess { index, .. } => has_side_effects(index),
+        TermKind::Paren(expression) => has_side_effects(expression),
+        TermKind::Unary { term, .. } => term_has_side_effects(term),
+        _ => false,
+    }
+}
+
+/// Whether `expression` can only be `true` (-1) or `false` (0): a comparison, a boolean
+/// constant, or `~`, `&` and `|` applied to those.
+pub fn is_boolean_valued(expression: &Expression) -> bool {
+    match expression {
+        Expression::Term(term) => is_boolean_term(term),
+        Expression::Binary { op, lhs, rhs, .. } => match op {
+            BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Eq => true,
+            BinaryOp::And | BinaryOp::Or => is_boolean_valued(lhs) && is_boolean_valued(rhs),
+            _ => false,
+        },
+    }
+}
+
+fn is_boolean_term(term: &Term) -> bool {
+    match &term.kind {
+        TermKind::KeywordConstant(KeywordConstant::True | KeywordConstant::False) => true,
+        TermKind::Paren(expression) => is_boolean_valued(expression),
+        TermKind::Unary {
+            op: UnaryOp::Not,
+            term,
+        } => is_boolean_term(term),
+        _ => false,
+    }
+}
+
+/// Adds the constants and enum members of `class` to `table`, in source order, so each
+/// value may use the ones declared before it. Returns an error for every value that is
+/// not known at compile time (that constant is then defined as 0) and every name that is
+/// already taken.
+pub fn define_constants(table: &mut symbol_table, class: &Class) -> Vec<Diagnostic> {
+    // (name, type, span, value, whether it is the first member of an enum)
+    let mut constants: Vec<(&str, &str, Span, Option<&Expression>, bool)> = class
+        .constants
+        .iter()
+        .map(|c| {
+            (
+                c.name.as_str(),
+                c.type_name.as_str(),
+                c.span,
+                Some(&c.value),
+                false,
+            )
+        })
+        .collect();
+    for enum_dec in &class.enums {
+        for (i, member) in enum_dec.members.iter().enumerate() {
+            let value = member.value.as_ref();
+            constants.push((&member.name, "int", member.span, value, i == 0));
+        }
+    }
+    constants.sort_by_key(|(_, _, span, _, _)| (span.line, span.column));
+
+    let mut errors = Vec::new();
+    // the value of the last enum member, which the next one without a value follows
+    let mut previous = -1i16;
+    for (name, type_name, span, value, first_member) in constants {
+        if first_member {
+            previous = -1;
+        }
+        let value = match value {
+            Some(expression) => constant_value_with(expression, &|n| table.constant_value(n))
+                .unwrap_or_else(|| {
+                    errors.push(Diagnostic::error(
+                        expression.span(),
+                        format!(
+                            "the value of constant '{}' is not known at compile time",
+                            name
+                        ),
+                    ));
+                    0
+                }),
+            None => previous.wrapping_add(1),
+        };
+        previous = value;
+        if table.kind_of(name).is_some() {
+            errors.push(Diagnostic::error(
+                span,
+                format!("'{}' is already defined in this class", name),
+            ));
+            continue;
+        }
+        table.define_constant(name, type_name, value);
+    }
+    errors
+}
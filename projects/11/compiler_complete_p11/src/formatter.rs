@@ -0,0 +1,263 @@
+use std::io;
+
+use crate::tokenizer::{TOKEN_TYPE, Token, jack_tokenizer};
+
+const INDENT: &str = "    ";
+
+/// Reprints a Jack source file in one canonical layout: four-space indentation, one
+/// statement per line, `{` at the end of the line it opens, single spaces around binary
+/// operators and after commas. Comments stay where they were and at most one blank line
+/// is kept between two lines. The result has exactly the tokens of `source`; that is
+/// checked before it is returned.
+pub fn format_source(source: &str, extensions: bool) -> io::Result<String> {
+    let tokens = jack_tokenizer::with_comments(source, extensions)?.tokens;
+    let formatted = Printer::new().print(&tokens);
+
+    let reformatted = jack_tokenizer::with_comments(&formatted, extensions)?.tokens;
+    if let Some(line) = first_difference(&tokens, &reformatted) {
+        return Err(io::Error::other(format!(
+            "formatting would change the tokens at line {}; the file was left as it is",
+            line
+        )));
+    }
+    Ok(formatted)
+}
+
+/// The source line of the first token that `after` does not reproduce. Comments only
+/// need the same words, since their continuation lines are re-indented.
+fn first_difference(before: &[Token], after: &[Token]) -> Option<usize> {
+    let same = |a: &Token, b: &Token| {
+        a.kind == b.kind
+            && if a.kind == TOKEN_TYPE::COMMENT {
+                a.value.split_whitespace().eq(b.value.split_whitespace())
+            } else {
+                a.value == b.value
+            }
+    };
+    match before.iter().zip(after).position(|(a, b)| !same(a, b)) {
+        Some(i) => Some(before[i].line),
+        None if before.len() != after.len() => Some(
+            before
+                .get(after.len())
+                .or(before.last())
+                .map_or(1, |t| t.line),
+        ),
+        None => None,
+    }
+}
+
+fn is_symbol(token: &Token, symbols: &[&str]) -> bool {
+    token.kind == TOKEN_TYPE::SYMBOL && symbols.contains(&token.value.as_str())
+}
+
+/// Whether an operand ends with `token`, which makes a following `-` a subtraction.
+fn ends_operand(token: &Token) -> bool {
+    match token.kind {
+        TOKEN_TYPE::IDENTIFIER
+        | TOKEN_TYPE::INT_CONST
+        | TOKEN_TYPE::STRING_CONST
+        | TOKEN_TYPE::CHAR_CONST => true,
+        TOKEN_TYPE::KEYWORD => matches!(token.value.as_str(), "true" | "false" | "null" | "this"),
+        TOKEN_TYPE::SYMBOL => is_symbol(token, &[")", "]"]),
+        TOKEN_TYPE::COMMENT => false,
+    }
+}
+
+fn is_unary(previous: Option<&Token>, token: &Token) -> bool {
+    is_symbol(token, &["~"]) || (is_symbol(token, &["-"]) && !previous.is_some_and(ends_operand))
+}
+
+/// Whether a space goes between `previous` and `token` on the same line.
+fn needs_space(previous: &Token, previous_unary: bool, token: &Token) -> bool {
+    if previous_unary
+        || is_symbol(token, &[";", ",", ")", "]", "."])
+        || is_symbol(previous, &["(", "[", "."])
+    {
+        return false;
+    }
+    if is_symbol(token, &["(", "["]) {
+        // a call or an array access, but `if (`, `while (` and `+ (`
+        return previous.kind != TOKEN_TYPE::IDENTIFIER;
+    }
+    true
+}
+
+/// How a token is written: strings and characters get back their quotes.
+fn text(token: &Token) -> String {
+    match token.kind {
+        TOKEN_TYPE::STRING_CONST => format!("\"{}\"", token.value),
+        TOKEN_TYPE::CHAR_CONST => format!("'{}'", token.value),
+        _ => token.value.clone(),
+    }
+}
+
+struct Printer {
+    out: String,
+    // open braces, which set the indentation
+    depth: usize,
+    // open parentheses; a `;` inside them (in a `for`) does not end the line
+    parens: usize,
+    // nothing but indentation is allowed on the current line yet
+    at_line_start: bool,
+    // the last token ends its line; the next one starts a new line
+    line_ended: bool,
+    // the last code token ended a statement or opened or closed a block, so a new line
+    // does not continue it
+    boundary: bool,
+    last_was_open: bool,
+    space_pending: bool,
+}
+
+impl Printer {
+    fn new() -> Self {
+        Self {
+            out: String::new(),
+            depth: 0,
+            parens: 0,
+            at_line_start: true,
+            line_ended: false,
+            boundary: true,
+            last_was_open: false,
+            space_pending: false,
+        }
+    }
+
+    fn newline(&mut self) {
+        self.out
+            .truncate(self.out.trim_end_matches([' ', '\t']).len());
+        self.out.push('\n');
+        self.at_line_start = true;
+    }
+
+    fn indentation(&self) -> String {
+        // a statement broken by a comment continues one level deeper
+        INDENT.repeat(self.depth + usize::from(!self.boundary))
+    }
+
+    /// Starts a new line, after a blank one if `blank` and the position allows it.
+    fn start_line(&mut self, blank: bool) {
+        if !self.out.is_empty() && !self.at_line_start {
+            self.newline();
+        }
+        if blank && !self.out.is_empty() && !self.last_was_open {
+            self.out.push('\n');
+        }
+        let indentation = self.indentation();
+        self.out.push_str(&indentation);
+        self.at_line_start = false;
+        self.line_ended = false;
+    }
+
+    /// Writes a comment, lining up the continuation lines of a block comment with the
+    /// current indentation.
+    fn push_comment(&mut self, comment: &str) {
+        let indentation = self.indentation();
+        for (i, line) in comment.lines().enumerate() {
+            if i > 0 {
+                self.newline();
+                let line = line.trim();
+                if !line.is_empty() {
+                    self.out.push_str(&indentation);
+                    if line.starts_with('*') {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(line);
+                }
+                self.at_line_start = false;
+            } else {
+                self.out.push_str(line.trim_end());
+            }
+        }
+    }
+
+    fn print(mut self, tokens: &[Token]) -> String {
+        let mut previous: Option<&Token> = None;
+        let mut previous_unary = false;
+        let mut comment_since_code = false;
+        // the source line the last token ended on
+        let mut last_line = 0;
+
+        for (i, token) in tokens.iter().enumerate() {
+            let blank_before = last_line > 0 && token.line > last_line + 1;
+
+            if token.kind == TOKEN_TYPE::COMMENT {
+                let end_line = token.line + token.value.matches('\n').count();
+                if !self.out.is_empty() && token.line == last_line {
+                    self.out.push(' ');
+                } else {
+                    self.start_line(blank_before);
+                }
+                self.push_comment(&token.value);
+                self.space_pending = false;
+                if token.value.starts_with("//")
+                    || tokens.get(i + 1).is_some_and(|next| next.line > end_line)
+                {
+                    self.line_ended = true;
+                } else {
+                    self.space_pending = true;
+                }
+                self.last_was_open = false;
+                comment_since_code = true;
+                last_line = end_line;
+                continue;
+            }
+
+            let is_close = is_symbol(token, &["}"]);
+            if is_close {
+                self.depth = self.depth.saturating_sub(1);
+                // an enum's last member has no `;` but is not continued by the `}`
+                self.boundary = true;
+            }
+            let joins_else = token.kind == TOKEN_TYPE::KEYWORD
+                && token.value == "else"
+                && previous.is_some_and(|p| is_symbol(p, &["}"]))
+                && !comment_since_code;
+            if joins_else {
+                self.out.push(' ');
+            } else if self.line_ended || self.out.is_empty() || is_close {
+                self.start_line(blank_before && !is_close);
+            } else if self.space_pending
+                || previous.is_some_and(|p| needs_space(p, previous_unary, token))
+            {
+                self.out.push(' ');
+            }
+            self.out.push_str(&text(token));
+
+            previous_unary = is_unary(previous, token);
+            previous = Some(token);
+            self.space_pending = false;
+            self.line_ended = false;
+            self.last_was_open = false;
+            self.boundary = false;
+            comment_since_code = false;
+            last_line = token.line;
+            if token.kind == TOKEN_TYPE::SYMBOL {
+                match token.value.as_str() {
+                    "(" => self.parens += 1,
+                    ")" => self.parens = self.parens.saturating_sub(1),
+                    "{" => {
+                        self.depth += 1;
+                        self.last_was_open = true;
+                        self.line_ended = true;
+                        self.boundary = true;
+                    }
+                    "}" => {
+                        self.line_ended = true;
+                        self.boundary = true;
+                    }
+                    ";" if self.parens == 0 => {
+                        self.line_ended = true;
+                        self.boundary = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut out = self.out.trim_end().to_string();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+}
@@ -0,0 +1,24 @@
+#![allow(dead_code, non_snake_case, non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+//! The Jack compiler's front end, checks and code generator, shared by the
+//! `compiler_complete_p11`, `jackfmt` and `jack_lsp` binaries. `compile_class` and
+//! `compile_project` compile sources held in memory.
+pub mod ast;
+pub mod class_index;
+pub mod code_generator;
+pub mod compiler;
+pub mod diagnostic;
+mod fold;
+pub mod formatter;
+pub mod json;
+pub mod lints;
+pub mod lsp;
+pub mod parser;
+pub mod semantic;
+pub mod symbol_table;
+pub mod tokenizer;
+pub mod type_checker;
+pub mod vm_writer;
+pub mod xml_writer;
+
+pub use compiler::{compile_class, compile_project};
@@ -0,0 +1,527 @@
+use std::collections::BTreeSet;
+
+use crate::ast::{
+    Class, ClassVarKind, Expression, Span, Statement, StatementKind, SubroutineCall, SubroutineDec,
+    Term, TermKind,
+};
+use crate::class_index::ClassIndex;
+use crate::diagnostic::Diagnostic;
+use crate::fold::define_constants;
+use crate::semantic::always_returns;
+use crate::symbol_table::symbol_table;
+
+/// A kind of warning about code that compiles but is probably a mistake. Each one can be
+/// turned off on the command line with `--allow <name>`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lint {
+    // a variable, parameter, field or static that is never read
+    Unused,
+    // a statement after a `return`
+    Unreachable,
+    // a subroutine no other part of the program calls
+    Uncalled,
+    // a local variable read before anything is assigned to it
+    Uninitialized,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::Unused,
+        Lint::Unreachable,
+        Lint::Uncalled,
+        Lint::Uninitialized,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::Unused => "unused",
+            Lint::Unreachable => "unreachable",
+            Lint::Uncalled => "uncalled",
+            Lint::Uninitialized => "uninitialized",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+/// Every `(class, subroutine)` that some call in `classes` resolves to.
+pub fn called_subroutines(classes: &[&Class], index: &ClassIndex) -> BTreeSet<(String, String)> {
+    let mut called = BTreeSet::new();
+    for class in classes {
+        let mut table = symbol_table::new();
+        for var_dec in &class.var_decs {
+            for (name, _) in &var_dec.names {
+                table.define(name, &var_dec.type_name, var_dec.kind.as_str());
+            }
+        }
+        define_constants(&mut table, class);
+        for subroutine in &class.subroutines {
+            table.start_subroutine();
+            for parameter in &subroutine.parameters {
+                table.define(&parameter.name, &parameter.type_name, "argument");
+            }
+            for var_dec in &subroutine.var_decs {
+                for (name, _) in &var_dec.names {
+                    table.define(name, &var_dec.type_name, "var");
+                }
+            }
+
+            let mut calls = Vec::new();
+            for statement in &subroutine.statements {
+                statement_calls(statement, &mut calls);
+            }
+            for call in calls {
+                let target = index.resolve_call(&class.name, &table, call);
+                called.insert((target.class_name, call.name.clone()));
+            }
+        }
+    }
+    called
+}
+
+/// The warnings for one class, leaving out the `allowed` kinds. `called` is the result of
+/// `called_subroutines` for the whole program, or `None` when only part of a program is
+/// being compiled and uncalled subroutines cannot be told apart from library code.
+pub fn check(
+    class: &Class,
+    called: Option<&BTreeSet<(String, String)>>,
+    allowed: &[Lint],
+) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        allowed,
+        warnings: Vec::new(),
+    };
+    linter.check_class(class, called);
+    linter
+        .warnings
+        .sort_by_key(|warning| (warning.span.line, warning.span.column));
+    linter.warnings
+}
+
+struct Linter<'a> {
+    allowed: &'a [Lint],
+    warnings: Vec<Diagnostic>,
+}
+
+/// A variable read or written somewhere in a subroutine.
+struct VariableUse<'e> {
+    name: &'e str,
+    is_write: bool,
+}
+
+impl<'a> Linter<'a> {
+    fn warn(&mut self, lint: Lint, span: Span, message: String) {
+        if !self.allowed.contains(&lint) {
+            self.warnings.push(Diagnostic::warning(
+                span,
+                format!("{} [{}]", message, lint.name()),
+            ));
+        }
+    }
+
+    fn check_class(&mut self, class: &Class, called: Option<&BTreeSet<(String, String)>>) {
+        // fields and statics can only be used by this class's own subroutines
+        let mut class_var_uses = Vec::new();
+        for subroutine in &class.subroutines {
+            let uses = self.check_subroutine(subroutine);
+            class_var_uses.extend(
+                uses.into_iter()
+                    .filter(|u| !declares_local(subroutine, u.name)),
+            );
+        }
+
+        for var_dec in &class.var_decs {
+            let kind = match var_dec.kind {
+                ClassVarKind::Static => "static variable",
+                ClassVarKind::Field => "field",
+            };
+            for (name, span) in &var_dec.names {
+                self.check_used(kind, name, *span, &class_var_uses);
+            }
+        }
+
+        if let Some(called) = called {
+            for subroutine in &class.subroutines {
+                let is_entry_point = matches!(
+                    (class.name.as_str(), subroutine.name.as_str()),
+                    ("Main", "main") | ("Sys", "init")
+                );
+                if !is_entry_point
+                    && !called.contains(&(class.name.clone(), subroutine.name.clone()))
+                {
+                    self.warn(
+                        Lint::Uncalled,
+                        subroutine.span,
+                        format!(
+                            "{} '{}.{}' is never called",
+                            subroutine.kind.as_str(),
+                            class.name,
+                            subroutine.name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks the subroutine's own variables and returns every variable use in it, so the
+    /// caller can check fields and statics.
+    fn check_subroutine<'e>(&mut self, subroutine: &'e SubroutineDec) -> Vec<VariableUse<'e>> {
+        let mut uses = Vec::new();
+        for statement in &subroutine.statements {
+            statement_uses(statement, &mut uses);
+        }
+
+        for parameter in &subroutine.parameters {
+            self.check_used("parameter", &parameter.name, parameter.span, &uses);
+        }
+        for var_dec in &subroutine.var_decs {
+            for (name, span) in &var_dec.names {
+                self.check_used("local variable", name, *span, &uses);
+            }
+        }
+
+        self.check_reachable(&subroutine.statements);
+
+        let mut flow = Flow {
+            locals: subroutine
+                .var_decs
+                .iter()
+                .flat_map(|v| v.names.iter().map(|(name, _)| name.as_str()))
+                .collect(),
+            reported: BTreeSet::new(),
+            reads_before_assignment: Vec::new(),
+        };
+        flow.statements(&subroutine.statements, BTreeSet::new());
+        for (name, span) in flow.reads_before_assignment {
+            self.warn(
+                Lint::Uninitialized,
+                span,
+                format!("local variable '{}' is read before it is assigned", name),
+            );
+        }
+
+        uses
+    }
+
+    fn check_used(&mut self, kind: &str, name: &str, span: Span, uses: &[VariableUse]) {
+        let mut uses = uses.iter().filter(|u| u.name == name).peekable();
+        if uses.clone().any(|u| !u.is_write) {
+            return;
+        }
+        let message = if uses.peek().is_some() {
+            format!("{} '{}' is assigned but never used", kind, name)
+        } else {
+            format!("{} '{}' is never used", kind, name)
+        };
+        self.warn(Lint::Unused, span, message);
+    }
+
+    /// Reports the first statement after one that always returns, or after `break` or
+    /// `continue`, in every block.
+    fn check_reachable(&mut self, statements: &[Statement]) {
+        for (i, statement) in statements.iter().enumerate() {
+            match &statement.kind {
+                StatementKind::If {
+                    then_branch,
+                    else_branch,
+                    ..
+                } => {
+                    self.check_reachable(then_branch);
+                    if let Some(else_branch) = else_branch {
+                        self.check_reachable(else_branch);
+                    }
+                }
+                StatementKind::While { body, .. } | StatementKind::For { body, .. } => {
+                    self.check_reachable(body)
+                }
+                _ => {}
+            }
+            if let Some(next) = statements.get(i + 1)
+                && (always_returns(std::slice::from_ref(statement))
+                    || matches!(
+                        statement.kind,
+                        StatementKind::Break | StatementKind::Continue
+                    ))
+            {
+                self.warn(
+                    Lint::Unreachable,
+                    next.span,
+                    "unreachable statement".to_string(),
+                );
+                return;
+            }
+        }
+    }
+}
+
+fn declares_local(subroutine: &SubroutineDec, name: &str) -> bool {
+    subroutine.parameters.iter().any(|p| p.name == name)
+        || subroutine
+            .var_decs
+            .iter()
+            .any(|v| v.names.iter().any(|(n, _)| n == name))
+}
+
+/// Definite-assignment analysis of a subroutine's locals. Jack zeroes locals on entry, so a
+/// read before assignment is not undefined, but it is usually a mistake.
+struct Flow<'e> {
+    locals: BTreeSet<&'e str>,
+    reported: BTreeSet<&'e str>,
+    reads_before_assignment: Vec<(&'e str, Span)>,
+}
+
+impl<'e> Flow<'e> {
+    fn read(&mut self, expression: &'e Expression, assigned: &BTreeSet<&'e str>) {
+        let mut reads = Vec::new();
+        expression_reads(expression, &mut reads);
+        for (name, span) in reads {
+            self.read_name(name, span, assigned);
+        }
+    }
+
+    fn read_name(&mut self, name: &'e str, span: Span, assigned: &BTreeSet<&'e str>) {
+        if self.locals.contains(name) && !assigned.contains(name) && self.reported.insert(name) {
+            self.reads_before_assignment.push((name, span));
+        }
+    }
+
+    /// Returns the locals assigned on every path through `statements`, or `None` if no
+    /// path reaches the end.
+    fn statements(
+        &mut self,
+        statements: &'e [Statement],
+        mut assigned: BTreeSet<&'e str>,
+    ) -> Option<BTreeSet<&'e str>> {
+        for statement in statements {
+            match &statement.kind {
+                StatementKind::Let { name, index, value } => {
+                    if let Some(index) = index {
+                        self.read_name(name, statement.span, &assigned);
+                        self.read(index, &assigned);
+                        self.read(value, &assigned);
+                    } else {
+                        self.read(value, &assigned);
+                        assigned.insert(name);
+                    }
+                }
+                StatementKind::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                } => {
+                    self.read(condition, &assigned);
+                    let after_then = self.statements(then_branch, assigned.clone());
+                    let after_else = match else_branch {
+                        Some(else_branch) => self.statements(else_branch, assigned.clone()),
+                        None => Some(assigned.clone()),
+                    };
+                    assigned = match (after_then, after_else) {
+                        (Some(then), Some(els)) => then.intersection(&els).copied().collect(),
+                        (Some(only), None) | (None, Some(only)) => only,
+                        (None, None) => return None,
+                    };
+                }
+                StatementKind::While { condition, body } => {
+                    self.read(condition, &assigned);
+                    // the body may not run at all
+                    self.statements(body, assigned.clone());
+                }
+                StatementKind::For {
+                    init,
+                    condition,
+                    step,
+                    body,
+                } => {
+                    assigned = self.statements(std::slice::from_ref(init), assigned)?;
+                    self.read(condition, &assigned);
+                    self.statements(body, assigned.clone());
+                    // `continue` can skip any assignment in the body
+                    self.statements(std::slice::from_ref(step), assigned.clone());
+                }
+                StatementKind::Do(call) => {
+                    let mut reads = Vec::new();
+                    call_reads(call, &mut reads);
+                    for (name, span) in reads {
+                        self.read_name(name, span, &assigned);
+                    }
+                }
+                StatementKind::Return(value) => {
+                    if let Some(value) = value {
+                        self.read(value, &assigned);
+                    }
+                    return None;
+                }
+                StatementKind::Break | StatementKind::Continue => return None,
+            }
+        }
+        Some(assigned)
+    }
+}
+
+fn statement_uses<'e>(statement: &'e Statement, uses: &mut Vec<VariableUse<'e>>) {
+    let mut reads = Vec::new();
+    match &statement.kind {
+        StatementKind::Let { name, index, value } => {
+            match index {
+                Some(index) => {
+                    reads.push((name.as_str(), statement.span));
+                    expression_reads(index, &mut reads);
+                }
+                None => uses.push(VariableUse {
+                    name,
+                    is_write: true,
+                }),
+            }
+            expression_reads(value, &mut reads);
+        }
+        StatementKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expression_reads(condition, &mut reads);
+            for statement in then_branch.iter().chain(else_branch.iter().flatten()) {
+                statement_uses(statement, uses);
+            }
+        }
+        StatementKind::While { condition, body } => {
+            expression_reads(condition, &mut reads);
+            for statement in body {
+                statement_uses(statement, uses);
+            }
+        }
+        StatementKind::For {
+            init,
+            condition,
+            step,
+            body,
+        } => {
+            expression_reads(condition, &mut reads);
+            for statement in [&**init, &**step].into_iter().chain(body) {
+                statement_uses(statement, uses);
+            }
+        }
+        StatementKind::Do(call) => call_reads(call, &mut reads),
+        StatementKind::Return(value) => {
+            if let Some(value) = value {
+                expression_reads(value, &mut reads);
+            }
+        }
+        StatementKind::Break | StatementKind::Continue => {}
+    }
+    uses.extend(reads.into_iter().map(|(name, _)| VariableUse {
+        name,
+        is_write: false,
+    }));
+}
+
+/// Every name `expression` reads: variables, arrays and call receivers. Receivers that
+/// turn out to be class names are harmless since they never match a declared variable.
+fn expression_reads<'e>(expression: &'e Expression, reads: &mut Vec<(&'e str, Span)>) {
+    match expression {
+        Expression::Term(term) => term_reads(term, reads),
+        Expression::Binary { lhs, rhs, .. } => {
+            expression_reads(lhs, reads);
+            expression_reads(rhs, reads);
+        }
+    }
+}
+
+fn term_reads<'e>(term: &'e Term, reads: &mut Vec<(&'e str, Span)>) {
+    match &term.kind {
+        TermKind::VarName(name) => reads.push((name, term.span)),
+        TermKind::ArrayAccess { name, index } => {
+            reads.push((name, term.span));
+            expression_reads(index, reads);
+        }
+        TermKind::Call(call) => call_reads(call, reads),
+        TermKind::Paren(expression) => expression_reads(expression, reads),
+        TermKind::Unary { term, .. } => term_reads(term, reads),
+        TermKind::IntegerConstant(_)
+        | TermKind::StringConstant(_)
+        | TermKind::KeywordConstant(_) => {}
+    }
+}
+
+fn call_reads<'e>(call: &'e SubroutineCall, reads: &mut Vec<(&'e str, Span)>) {
+    if let Some(receiver) = &call.receiver {
+        reads.push((receiver, call.span));
+    }
+    for arg in &call.args {
+        expression_reads(arg, reads);
+    }
+}
+
+fn statement_calls<'e>(statement: &'e Statement, calls: &mut Vec<&'e SubroutineCall>) {
+    let mut expressions: Vec<&Expression> = Vec::new();
+    match &statement.kind {
+        StatementKind::Let { index, value, .. } => {
+            expressions.extend(index);
+            expressions.push(value);
+        }
+        StatementKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expressions.push(condition);
+            for statement in then_branch.iter().chain(else_branch.iter().flatten()) {
+                statement_calls(statement, calls);
+            }
+        }
+        StatementKind::While { condition, body } => {
+            expressions.push(condition);
+            for statement in body {
+                statement_calls(statement, calls);
+            }
+        }
+        StatementKind::For {
+            init,
+            condition,
+            step,
+            body,
+        } => {
+            expressions.push(condition);
+            for statement in [&**init, &**step].into_iter().chain(body) {
+                statement_calls(statement, calls);
+            }
+        }
+        StatementKind::Do(call) => {
+            calls.push(call);
+            expressions.extend(&call.args);
+        }
+        StatementKind::Return(value) => expressions.extend(value),
+        StatementKind::Break | StatementKind::Continue => {}
+    }
+    for expression in expressions {
+        expression_calls(expression, calls);
+    }
+}
+
+fn expression_calls<'e>(expression: &'e Expression, calls: &mut Vec<&'e SubroutineCall>) {
+    match expression {
+        Expression::Term(term) => term_calls(term, calls),
+        Expression::Binary { lhs, rhs, .. } => {
+            expression_calls(lhs, calls);
+            expression_calls(rhs, calls);
+        }
+    }
+}
+
+fn term_calls<'e>(term: &'e Term, calls: &mut Vec<&'e SubroutineCall>) {
+    match &term.kind {
+        TermKind::ArrayAccess { index, .. } => expression_calls(index, calls),
+        TermKind::Call(call) => {
+            calls.push(call);
+            for arg in &call.args {
+                expression_calls(arg, calls);
+            }
+        }
+        TermKind::Paren(expression) => expression_calls(expression, calls),
+        TermKind::Unary { term, .. } => term_calls(term, calls),
+        _ => {}
+    }
+}
@@ -0,0 +1,773 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Class, Span, SubroutineDec, SubroutineKind};
+use crate::class_index::{ClassIndex, OS_API};
+use crate::diagnostic::{self, Diagnostic, Severity};
+use crate::fold::define_constants;
+use crate::json::Json;
+use crate::parser::compilation_engine;
+use crate::symbol_table::{Identifier_Kind, symbol_table};
+use crate::tokenizer::{TOKEN_TYPE, Token, jack_tokenizer};
+use crate::{lints, semantic, type_checker};
+
+// JSON-RPC error codes: a message that is not JSON, a request the server does not know,
+// and a known request with unusable parameters
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn error_reply(id: &Json, code: i64, message: String) -> Json {
+    Json::object([
+        ("jsonrpc", Json::string("2.0")),
+        ("id", id.clone()),
+        (
+            "error",
+            Json::object([
+                ("code", Json::Number(code as f64)),
+                ("message", Json::String(message)),
+            ]),
+        ),
+    ])
+}
+
+/// Longest message body `read_message` accepts; a larger `Content-Length` is an error
+/// rather than an allocation that could take the server down.
+pub const MAX_MESSAGE_LENGTH: usize = 8 << 20;
+
+/// The reply to a message `read_message` could not read, which has no id to answer.
+pub fn parse_error(err: &io::Error) -> Json {
+    error_reply(&Json::Null, PARSE_ERROR, err.to_string())
+}
+
+/// Reads one `Content-Length` framed message; `None` once the input is closed. A message
+/// with a bad header or body is an `InvalidData` error, read to its end so the next one
+/// can still be read.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = Err("message without a Content-Length header");
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = value
+                .trim()
+                .parse()
+                .map_err(|_| "bad Content-Length header");
+        }
+    }
+    let length = length
+        .and_then(|length| {
+            (length <= MAX_MESSAGE_LENGTH)
+                .then_some(length)
+                .ok_or("Content-Length is too large")
+        })
+        .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Json::parse(&String::from_utf8_lossy(&body)).map(Some)
+}
+
+pub fn write_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+/// The path of a `file://` URI, with `%XX` escapes decoded.
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::new();
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = (encoded[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            None => {
+                bytes.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// An LSP position, 0-based, from a span, which is 1-based.
+fn position(span: Span) -> Json {
+    Json::object([
+        ("line", Json::number(span.line.saturating_sub(1))),
+        ("character", Json::number(span.column.saturating_sub(1))),
+    ])
+}
+
+/// The file and position of a `TextDocumentPositionParams`.
+fn document_position(params: &Json) -> Option<(PathBuf, Span)> {
+    let path = uri_to_path(params.get("textDocument").get("uri").as_str()?)?;
+    let at = params.get("position");
+    let span = Span {
+        line: at.get("line").as_usize()? + 1,
+        column: at.get("character").as_usize()? + 1,
+    };
+    Some((path, span))
+}
+
+fn range(span: Span, length: usize) -> Json {
+    let end = Span {
+        column: span.column + length,
+        ..span
+    };
+    Json::object([("start", position(span)), ("end", position(end))])
+}
+
+fn lsp_diagnostic(diagnostic: &Diagnostic, source: &str) -> Json {
+    let line = source
+        .lines()
+        .nth(diagnostic.span.line.wrapping_sub(1))
+        .unwrap_or("");
+    let mut message = diagnostic.message.clone();
+    if let Some(expected) = diagnostic.expected_text() {
+        message = format!("{}; {}", message, expected);
+    }
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+    Json::object([
+        (
+            "range",
+            range(
+                diagnostic.span,
+                diagnostic::token_length(line, diagnostic.span.column),
+            ),
+        ),
+        ("severity", Json::number(severity)),
+        ("source", Json::string("jack")),
+        ("message", Json::String(message)),
+    ])
+}
+
+fn is_symbol(token: Option<&Token>, symbol: &str) -> bool {
+    token.is_some_and(|t| t.kind == TOKEN_TYPE::SYMBOL && t.value == symbol)
+}
+
+/// The span of the first `name` identifier at or after `from`: declarations keep the
+/// position of their first token, which is the keyword or the type.
+fn name_span(tokens: &[Token], from: Span, name: &str) -> Span {
+    tokens
+        .iter()
+        .find(|t| {
+            (t.line, t.column) >= (from.line, from.column)
+                && t.kind == TOKEN_TYPE::IDENTIFIER
+                && t.value == name
+        })
+        .map_or(from, |t| Span {
+            line: t.line,
+            column: t.column,
+        })
+}
+
+/// The subroutine `span` is in, if any.
+fn subroutine_at(class: &Class, span: Span) -> Option<&SubroutineDec> {
+    class
+        .subroutines
+        .iter()
+        .rev()
+        .find(|s| (s.span.line, s.span.column) <= (span.line, span.column))
+}
+
+/// The names visible at `span`, defined the way the code generator defines them.
+fn scope_at(class: &Class, span: Span) -> symbol_table {
+    let mut table = symbol_table::new();
+    for var_dec in &class.var_decs {
+        for (name, _) in &var_dec.names {
+            table.define(name, &var_dec.type_name, var_dec.kind.as_str());
+        }
+    }
+    define_constants(&mut table, class);
+    if let Some(subroutine) = subroutine_at(class, span) {
+        table.start_subroutine();
+        if subroutine.kind == SubroutineKind::Method {
+            table.define("this", &class.name, "argument");
+        }
+        for parameter in &subroutine.parameters {
+            table.define(&parameter.name, &parameter.type_name, "argument");
+        }
+        for var_dec in &subroutine.var_decs {
+            for (name, _) in &var_dec.names {
+                table.define(name, &var_dec.type_name, "var");
+            }
+        }
+    }
+    table
+}
+
+/// Where a variable is declared, as the span of the declaration.
+fn variable_declaration(class: &Class, at: Span, name: &str) -> Option<Span> {
+    if let Some(subroutine) = subroutine_at(class, at) {
+        if let Some(parameter) = subroutine.parameters.iter().find(|p| p.name == name) {
+            return Some(parameter.span);
+        }
+        let mut locals = subroutine.var_decs.iter().flat_map(|v| &v.names);
+        if let Some((_, span)) = locals.find(|(n, _)| n == name) {
+            return Some(*span);
+        }
+    }
+    let mut class_vars = class.var_decs.iter().flat_map(|v| &v.names);
+    if let Some((_, span)) = class_vars.find(|(n, _)| n == name) {
+        return Some(*span);
+    }
+    if let Some(constant) = class.constants.iter().find(|c| c.name == name) {
+        return Some(constant.span);
+    }
+    class
+        .enums
+        .iter()
+        .flat_map(|e| &e.members)
+        .find(|m| m.name == name)
+        .map(|m| m.span)
+}
+
+/// What an identifier in the source refers to.
+enum Target {
+    Variable(String),
+    Subroutine { class_name: String, name: String },
+    Class(String),
+}
+
+/// Works out what `tokens[i]` names from the tokens around it: `x.name` and `name(` are
+/// subroutines, a name in scope is a variable, and anything else is a class.
+fn target(tokens: &[Token], i: usize, class: &Class, table: &symbol_table) -> Target {
+    let name = tokens[i].value.clone();
+    if i >= 2 && is_symbol(tokens.get(i - 1), ".") {
+        let receiver = &tokens[i - 2].value;
+        let class_name = table.type_of(receiver).unwrap_or(receiver).clone();
+        return Target::Subroutine { class_name, name };
+    }
+    if is_symbol(tokens.get(i + 1), "(") {
+        return Target::Subroutine {
+            class_name: class.name.clone(),
+            name,
+        };
+    }
+    if table.kind_of(&name).is_some() {
+        Target::Variable(name)
+    } else {
+        Target::Class(name)
+    }
+}
+
+fn hover_text(target: &Target, table: &symbol_table, index: &ClassIndex) -> Option<String> {
+    let code = |text: String| format!("```jack\n{}\n```", text);
+    match target {
+        Target::Variable(name) => {
+            let entry = table.entry(name)?;
+            let keyword = match entry.kind {
+                Identifier_Kind::STATIC => "static",
+                Identifier_Kind::FIELD => "field",
+                Identifier_Kind::ARG => "argument",
+                Identifier_Kind::VAR => "var",
+                Identifier_Kind::CONST(_) => "const",
+            };
+            let declaration = format!("{} {} {}", keyword, entry.type_name, entry.name);
+            Some(match entry.kind {
+                Identifier_Kind::CONST(value) => code(format!("{} = {}", declaration, value)),
+                _ => format!(
+                    "{}\n\n`{} {}`",
+                    code(declaration),
+                    entry.kind.kind_to_segment(),
+                    entry.index
+                ),
+            })
+        }
+        Target::Subroutine { class_name, name } => {
+            let info = index.subroutine(class_name, name)?;
+            Some(code(info.signature(class_name, name)))
+        }
+        Target::Class(name) => index
+            .contains_class(name)
+            .then(|| code(format!("class {}", name))),
+    }
+}
+
+/// A `.jack` file of the project, parsed from the editor's text if it is open.
+struct ProjectFile {
+    path: PathBuf,
+    source: String,
+    // the class as parsed now, or as it last parsed while the file has errors
+    class: Option<Class>,
+    parsed: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Every class in one folder, which is what the compiler compiles together.
+struct Project {
+    files: Vec<ProjectFile>,
+    index: ClassIndex,
+}
+
+impl Project {
+    fn file(&self, path: &Path) -> Option<&ProjectFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+
+    fn class_file(&self, class_name: &str) -> Option<&ProjectFile> {
+        self.files
+            .iter()
+            .find(|f| f.class.as_ref().is_some_and(|c| c.name == class_name))
+    }
+}
+
+struct Document {
+    // the URI the editor uses, which diagnostics must be published under
+    uri: String,
+    text: String,
+}
+
+/// Answers the editor's messages. Each request re-reads the folder of its file, with the
+/// text of open documents in place of what is saved, so other classes are always current.
+pub struct Server {
+    extensions: bool,
+    precedence: bool,
+    typecheck: bool,
+    documents: HashMap<PathBuf, Document>,
+    // the last class each file parsed to, used while it does not parse
+    last_parsed: HashMap<PathBuf, Class>,
+    shutdown: bool,
+    exit_code: Option<i32>,
+}
+
+impl Server {
+    pub fn new(extensions: bool, precedence: bool, typecheck: bool) -> Self {
+        Self {
+            extensions,
+            precedence,
+            typecheck,
+            documents: HashMap::new(),
+            last_parsed: HashMap::new(),
+            shutdown: false,
+            exit_code: None,
+        }
+    }
+
+    /// Set once the editor sent `exit`: 0 after a `shutdown` request, 1 without one.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Handles one message and returns the messages to send back.
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let Some(method) = message.get("method").as_str() else {
+            // a response to a request of ours; we make none
+            return Vec::new();
+        };
+        let params = message.get("params");
+        let id = message.get("id");
+        if id.is_null() {
+            return self.notification(method, params);
+        }
+
+        let reply = match self.request(method, params) {
+            Ok(result) => Json::object([
+                ("jsonrpc", Json::string("2.0")),
+                ("id", id.clone()),
+                ("result", result),
+            ]),
+            Err((code, message)) => error_reply(id, code, message),
+        };
+        vec![reply]
+    }
+
+    fn request(&mut self, method: &str, params: &Json) -> Result<Json, (i64, String)> {
+        let position_request = matches!(
+            method,
+            "textDocument/definition" | "textDocument/hover" | "textDocument/completion"
+        );
+        if position_request && document_position(params).is_none() {
+            return Err((
+                INVALID_PARAMS,
+                format!("{} needs a file:// textDocument uri and a position", method),
+            ));
+        }
+        let result = match method {
+            "initialize" => Json::object([
+                (
+                    "capabilities",
+                    Json::object([
+                        (
+                            "textDocumentSync",
+                            Json::object([
+                                ("openClose", Json::Bool(true)),
+                                // the whole text on every change
+                                ("change", Json::number(1)),
+                                ("save", Json::object([("includeText", Json::Bool(true))])),
+                            ]),
+                        ),
+                        ("definitionProvider", Json::Bool(true)),
+                        ("hoverProvider", Json::Bool(true)),
+                        (
+                            "completionProvider",
+                            Json::object([(
+                                "triggerCharacters",
+                                Json::Array(vec![Json::string(".")]),
+                            )]),
+                        ),
+                    ]),
+                ),
+                (
+                    "serverInfo",
+                    Json::object([("name", Json::string("jack_lsp"))]),
+                ),
+            ]),
+            "shutdown" => {
+                self.shutdown = true;
+                Json::Null
+            }
+            "textDocument/definition" => self.definition(params).unwrap_or(Json::Null),
+            "textDocument/hover" => self.hover(params).unwrap_or(Json::Null),
+            "textDocument/completion" => Json::Array(self.completion(params).unwrap_or_default()),
+            _ => return Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+        };
+        Ok(result)
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        if method == "exit" {
+            self.exit_code = Some(if self.shutdown { 0 } else { 1 });
+            return Vec::new();
+        }
+        let document = params.get("textDocument");
+        let uri = document.get("uri").as_str().unwrap_or("");
+        let Some(path) = uri_to_path(uri) else {
+            return Vec::new();
+        };
+        match method {
+            "textDocument/didOpen" => {
+                self.open(&path, uri, document.get("text"));
+                self.publish_diagnostics(&path)
+            }
+            "textDocument/didChange" => {
+                if let Json::Array(changes) = params.get("contentChanges")
+                    && let Some(change) = changes.last()
+                {
+                    self.open(&path, uri, change.get("text"));
+                }
+                Vec::new()
+            }
+            "textDocument/didSave" => {
+                self.open(&path, uri, params.get("text"));
+                self.publish_diagnostics(&path)
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&path);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Keeps `text` as the document's content; without text the saved file is read.
+    fn open(&mut self, path: &Path, uri: &str, text: &Json) {
+        let text = match text.as_str() {
+            Some(text) => text.to_string(),
+            None => match fs::read_to_string(path) {
+                Ok(text) => text,
+                Err(_) => return,
+            },
+        };
+        self.documents.insert(
+            path.to_path_buf(),
+            Document {
+                uri: uri.to_string(),
+                text,
+            },
+        );
+    }
+
+    fn uri(&self, path: &Path) -> String {
+        match self.documents.get(path) {
+            Some(document) => document.uri.clone(),
+            None => path_to_uri(path),
+        }
+    }
+
+    /// Tokenizes and parses one file, keeping its errors.
+    fn parse(&mut self, path: PathBuf, source: String) -> ProjectFile {
+        let mut diagnostics = Vec::new();
+        let mut class = None;
+        match jack_tokenizer::new(&source, self.extensions) {
+            Ok(tokens) => {
+                let mut parser = compilation_engine::new(tokens.tokens)
+                    .with_precedence(self.precedence)
+                    .with_extensions(self.extensions);
+                let parsed = parser.compile_class();
+                diagnostics.extend(parser.take_warnings());
+                match parsed {
+                    Ok(parsed) => class = Some(parsed),
+                    Err(errors) => diagnostics.extend(errors),
+                }
+            }
+            Err(err) => diagnostics.extend(diagnostic::from_io_error(&err).cloned()),
+        }
+
+        let parsed = class.is_some();
+        match &class {
+            Some(class) => {
+                self.last_parsed.insert(path.clone(), class.clone());
+            }
+            None => class = self.last_parsed.get(&path).cloned(),
+        }
+        ProjectFile {
+            path,
+            source,
+            class,
+            parsed,
+            diagnostics,
+        }
+    }
+
+    /// Parses every `.jack` file in `dir`, plus open documents there not saved yet.
+    fn load_project(&mut self, dir: &Path) -> Project {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "jack"))
+            .collect();
+        for path in self.documents.keys() {
+            if path.parent() == Some(dir) && !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths.sort();
+
+        let mut files = Vec::new();
+        for path in paths {
+            let source = match self.documents.get(&path) {
+                Some(document) => document.text.clone(),
+                None => match fs::read_to_string(&path) {
+                    Ok(source) => source,
+                    Err(_) => continue,
+                },
+            };
+            files.push(self.parse(path, source));
+        }
+
+        let mut index = ClassIndex::new();
+        index
+            .add_api(OS_API)
+            .expect("the built-in OS API description is valid");
+        for class in files.iter().filter_map(|f| f.class.as_ref()) {
+            index.add_class(class);
+        }
+        Project { files, index }
+    }
+
+    /// The checks the compiler makes, on every file of the folder of `path`, since an edit
+    /// to one class can break calls in the others.
+    fn publish_diagnostics(&mut self, path: &Path) -> Vec<Json> {
+        let Some(dir) = path.parent() else {
+            return Vec::new();
+        };
+        let mut project = self.load_project(dir);
+
+        let classes: Vec<&Class> = project
+            .files
+            .iter()
+            .filter_map(|f| f.class.as_ref())
+            .collect();
+        let called = classes
+            .iter()
+            .any(|class| class.name == "Main")
+            .then(|| lints::called_subroutines(&classes, &project.index));
+        let mut found = Vec::new();
+        for file in &project.files {
+            let mut diagnostics = Vec::new();
+            if let Some(class) = file.class.as_ref().filter(|_| file.parsed) {
+                let errors = semantic::check(class, &project.index);
+                // like the compiler, warnings only for classes without errors
+                if errors.is_empty() {
+                    diagnostics.extend(lints::check(class, called.as_ref(), &[]));
+                    if self.typecheck {
+                        diagnostics.extend(type_checker::check(class, &project.index));
+                    }
+                }
+                diagnostics.extend(errors);
+            }
+            found.push(diagnostics);
+        }
+        for (file, diagnostics) in project.files.iter_mut().zip(found) {
+            file.diagnostics.extend(diagnostics);
+        }
+
+        project
+            .files
+            .iter()
+            .map(|file| {
+                let diagnostics = file
+                    .diagnostics
+                    .iter()
+                    .map(|d| lsp_diagnostic(d, &file.source))
+                    .collect();
+                Json::object([
+                    ("jsonrpc", Json::string("2.0")),
+                    ("method", Json::string("textDocument/publishDiagnostics")),
+                    (
+                        "params",
+                        Json::object([
+                            ("uri", Json::String(self.uri(&file.path))),
+                            ("diagnostics", Json::Array(diagnostics)),
+                        ]),
+                    ),
+                ])
+            })
+            .collect()
+    }
+
+    /// The file and 1-based position of a `textDocument/...` request, and the project.
+    fn locate(&mut self, params: &Json) -> Option<(Project, PathBuf, Span)> {
+        let (path, span) = document_position(params)?;
+        let project = self.load_project(path.parent()?);
+        Some((project, path, span))
+    }
+
+    /// The identifier under the cursor and what it refers to.
+    fn target_at(
+        &mut self,
+        params: &Json,
+    ) -> Option<(Project, PathBuf, Token, Target, symbol_table)> {
+        let (project, path, span) = self.locate(params)?;
+        let file = project.file(&path)?;
+        let class = file.class.as_ref()?;
+        let tokens = jack_tokenizer::new(&file.source, self.extensions)
+            .ok()?
+            .tokens;
+        let i = tokens.iter().position(|t| {
+            t.kind == TOKEN_TYPE::IDENTIFIER
+                && t.line == span.line
+                && (t.column..=t.column + t.value.chars().count()).contains(&span.column)
+        })?;
+        let table = scope_at(class, span);
+        let target = target(&tokens, i, class, &table);
+        let token = tokens[i].clone();
+        Some((project, path, token, target, table))
+    }
+
+    fn definition(&mut self, params: &Json) -> Option<Json> {
+        let (project, path, token, target, _) = self.target_at(params)?;
+        let at = Span {
+            line: token.line,
+            column: token.column,
+        };
+        let (file, declaration, name) = match &target {
+            Target::Variable(name) => {
+                let file = project.file(&path)?;
+                let declaration = variable_declaration(file.class.as_ref()?, at, name)?;
+                (file, declaration, name)
+            }
+            Target::Subroutine { class_name, name } => {
+                let file = project.class_file(class_name)?;
+                let class = file.class.as_ref()?;
+                let subroutine = class.subroutines.iter().find(|s| &s.name == name)?;
+                (file, subroutine.span, name)
+            }
+            Target::Class(name) => {
+                let file = project.class_file(name)?;
+                (file, file.class.as_ref()?.span, name)
+            }
+        };
+        let tokens = jack_tokenizer::new(&file.source, self.extensions)
+            .ok()?
+            .tokens;
+        let span = name_span(&tokens, declaration, name);
+        Some(Json::object([
+            ("uri", Json::String(self.uri(&file.path))),
+            ("range", range(span, name.chars().count())),
+        ]))
+    }
+
+    fn hover(&mut self, params: &Json) -> Option<Json> {
+        let (project, _, token, target, table) = self.target_at(params)?;
+        let text = hover_text(&target, &table, &project.index)?;
+        let span = Span {
+            line: token.line,
+            column: token.column,
+        };
+        Some(Json::object([
+            (
+                "contents",
+                Json::object([
+                    ("kind", Json::string("markdown")),
+                    ("value", Json::String(text)),
+                ]),
+            ),
+            ("range", range(span, token.value.chars().count())),
+        ]))
+    }
+
+    /// After `Name.`: the functions and constructors of class `Name`, or the methods of
+    /// the class of variable `Name`.
+    fn completion(&mut self, params: &Json) -> Option<Vec<Json>> {
+        let (project, path, span) = self.locate(params)?;
+        let file = project.file(&path)?;
+        let line = file.source.lines().nth(span.line - 1)?;
+        let before: String = line.chars().take(span.column - 1).collect();
+        let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let receiver = before.trim_end_matches(is_name).strip_suffix('.')?;
+        let receiver = receiver.rsplit(|c| !is_name(c)).next()?;
+        if receiver.is_empty() {
+            return None;
+        }
+
+        let table = scope_at(file.class.as_ref()?, span);
+        let (class_name, on_object) = match table.type_of(receiver) {
+            Some(type_name) => (type_name.as_str(), true),
+            None => (receiver, false),
+        };
+        let items = project
+            .index
+            .subroutines(class_name)
+            .filter(|(_, info)| (info.kind == SubroutineKind::Method) == on_object)
+            .map(|(name, info)| {
+                // LSP completion item kinds
+                let kind = match info.kind {
+                    SubroutineKind::Method => 2,
+                    SubroutineKind::Function => 3,
+                    SubroutineKind::Constructor => 4,
+                };
+                Json::object([
+                    ("label", Json::string(name)),
+                    ("kind", Json::number(kind)),
+                    ("detail", Json::String(info.signature(class_name, name))),
+                ])
+            })
+            .collect();
+        Some(items)
+    }
+}
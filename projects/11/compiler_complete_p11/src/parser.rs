@@ -0,0 +1,864 @@
+use std::iter::Peekable;
+use std::vec;
+
+use crate::ast::{
+    BinaryOp, Class, ClassVarDec, ClassVarKind, ConstDec, EnumDec, EnumMember, Expression,
+    KeywordConstant, Parameter, Span, Statement, StatementKind, SubroutineCall, SubroutineDec,
+    SubroutineKind, Term, TermKind, UnaryOp, VarDec,
+};
+use crate::diagnostic::Diagnostic;
+use crate::tokenizer::{TOKEN_TYPE, Token, hack_char_code, int_value};
+
+// `for`, `break` and `continue` only come out of the tokenizer as keywords with
+// `--extensions`
+const STATEMENT_KEYWORDS: [&str; 8] = [
+    "let", "if", "while", "do", "return", "for", "break", "continue",
+];
+
+const TERM_START: [&str; 7] = [
+    "integer constant",
+    "string constant",
+    "keyword constant",
+    "identifier",
+    "'('",
+    "'-'",
+    "'~'",
+];
+
+/// Recursive-descent parser over the token stream, producing one `ast::Class` per file.
+///
+/// A syntax error inside a statement is recorded and the parser skips ahead to the next
+/// statement (panic-mode recovery), so one run can report several errors.
+pub struct compilation_engine {
+    tokens: Vec<Token>,
+    pos: usize,
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+    // group operators by conventional precedence instead of strictly left to right
+    precedence: bool,
+    // accept the `--extensions` statements and `else if`
+    extensions: bool,
+    // how many loops enclose the statement being parsed, for `break` and `continue`
+    loop_depth: usize,
+}
+
+/// The operators and terms that follow the first term of an expression.
+type OperatorChain = Peekable<vec::IntoIter<(BinaryOp, Span, Expression)>>;
+
+fn binary(op: BinaryOp, span: Span, lhs: Expression, rhs: Expression) -> Expression {
+    Expression::Binary {
+        op,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+        span,
+    }
+}
+
+/// Precedence climbing: folds operators binding at least as tightly as `min_precedence`
+/// into `lhs`, letting tighter operators further right take their left operand first.
+fn climb(mut lhs: Expression, chain: &mut OperatorChain, min_precedence: u8) -> Expression {
+    while let Some((op, span, mut rhs)) =
+        chain.next_if(|(op, _, _)| op.precedence() >= min_precedence)
+    {
+        while chain
+            .peek()
+            .is_some_and(|(next, _, _)| next.precedence() > op.precedence())
+        {
+            rhs = climb(rhs, chain, op.precedence() + 1);
+        }
+        lhs = binary(op, span, lhs, rhs);
+    }
+    lhs
+}
+
+fn span_of(token: &Token) -> Span {
+    Span {
+        line: token.line,
+        column: token.column,
+    }
+}
+
+impl compilation_engine {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            precedence: false,
+            extensions: false,
+            loop_depth: 0,
+        }
+    }
+
+    pub fn with_precedence(mut self, precedence: bool) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    pub fn with_extensions(mut self, extensions: bool) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Warnings found while parsing; they do not stop the class from compiling.
+    pub fn take_warnings(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        if self.pos >= self.tokens.len() {
+            return None;
+        }
+        let idx = self.pos;
+        self.pos += 1;
+        Some(self.tokens[idx].clone())
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).cloned()
+    }
+
+    fn peek_is(&self, value: &str) -> bool {
+        self.tokens.get(self.pos).is_some_and(|t| t.value == value)
+    }
+
+    /// An error at the current token (or at the end of the last one) saying what was
+    /// expected instead.
+    fn unexpected(&self, expected: &[&str]) -> Diagnostic {
+        match self.tokens.get(self.pos) {
+            Some(tok) => Diagnostic::error(
+                span_of(tok),
+                format!("unexpected {} '{}'", tok.kind.description(), tok.value),
+            ),
+            None => {
+                let span = self
+                    .tokens
+                    .last()
+                    .map(|tok| Span {
+                        line: tok.line,
+                        column: tok.column + tok.value.chars().count(),
+                    })
+                    .unwrap_or(Span { line: 1, column: 1 });
+                Diagnostic::error(span, "unexpected end of file".to_string())
+            }
+        }
+        .with_expected(expected)
+    }
+
+    /// Consumes the next token if `accept` likes it; otherwise leaves it for recovery.
+    fn expect_with(
+        &mut self,
+        accept: impl Fn(&Token) -> bool,
+        expected: &[&str],
+    ) -> Result<Token, Diagnostic> {
+        match self.tokens.get(self.pos) {
+            Some(tok) if accept(tok) => Ok(self.advance().unwrap()),
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn expect_kind(&mut self, expected_kind: TOKEN_TYPE) -> Result<Token, Diagnostic> {
+        let expected = expected_kind.description();
+        self.expect_with(|tok| tok.kind == expected_kind, &[expected])
+    }
+
+    fn expect_value(&mut self, expected_value: &str) -> Result<Token, Diagnostic> {
+        let expected = format!("'{}'", expected_value);
+        self.expect_with(|tok| tok.value == expected_value, &[expected.as_str()])
+    }
+
+    /// `int`, `char`, `boolean` or a class name.
+    fn expect_type(&mut self) -> Result<Token, Diagnostic> {
+        self.expect_with(
+            |tok| {
+                tok.kind == TOKEN_TYPE::IDENTIFIER
+                    || matches!(tok.value.as_str(), "int" | "char" | "boolean")
+            },
+            &["'int'", "'char'", "'boolean'", "class name"],
+        )
+    }
+
+    /// A type, or `void`.
+    fn expect_return_type(&mut self) -> Result<Token, Diagnostic> {
+        if self.peek_is("void") {
+            return self.expect_value("void");
+        }
+        self.expect_with(
+            |tok| {
+                tok.kind == TOKEN_TYPE::IDENTIFIER
+                    || matches!(tok.value.as_str(), "int" | "char" | "boolean")
+            },
+            &["'void'", "'int'", "'char'", "'boolean'", "class name"],
+        )
+    }
+
+    fn peek_operator(&self) -> Option<BinaryOp> {
+        self.tokens
+            .get(self.pos)
+            .filter(|x| x.kind == TOKEN_TYPE::SYMBOL)
+            .and_then(|x| BinaryOp::from_symbol(&x.value))
+    }
+
+    /// Parses a whole class. Every syntax error found is returned, not just the first.
+    pub fn compile_class(&mut self) -> Result<Class, Vec<Diagnostic>> {
+        let result = self.compile_class_dec().and_then(|class| {
+            if self.pos < self.tokens.len() {
+                Err(self.unexpected(&["end of file"]))
+            } else {
+                Ok(class)
+            }
+        });
+
+        match result {
+            Ok(class) if self.errors.is_empty() => Ok(class),
+            Ok(_) => Err(std::mem::take(&mut self.errors)),
+            Err(error) => {
+                self.errors.push(error);
+                Err(std::mem::take(&mut self.errors))
+            }
+        }
+    }
+
+    fn compile_class_dec(&mut self) -> Result<Class, Diagnostic> {
+        let class_tok = self.expect_value("class")?;
+        let class_identifier = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?; // className
+        self.expect_value("{")?;
+
+        let mut var_decs = Vec::new();
+        let mut constants = Vec::new();
+        let mut enums = Vec::new();
+        let mut subroutines = Vec::new();
+
+        loop {
+            if self.peek_is("static") || self.peek_is("field") {
+                var_decs.push(self.compile_class_var_dec()?);
+            } else if self.extensions && self.peek_is("const") {
+                constants.push(self.compile_const_dec()?);
+            } else if self.extensions && self.peek_is("enum") {
+                enums.push(self.compile_enum_dec()?);
+            } else if self.peek_is("constructor")
+                || self.peek_is("function")
+                || self.peek_is("method")
+            {
+                subroutines.push(self.compile_subroutine()?);
+            } else if self.peek_is("}") {
+                self.advance();
+                break;
+            } else if self.extensions {
+                return Err(self.unexpected(&[
+                    "'static'",
+                    "'field'",
+                    "'const'",
+                    "'enum'",
+                    "'constructor'",
+                    "'function'",
+                    "'method'",
+                    "'}'",
+                ]));
+            } else {
+                return Err(self.unexpected(&[
+                    "'static'",
+                    "'field'",
+                    "'constructor'",
+                    "'function'",
+                    "'method'",
+                    "'}'",
+                ]));
+            }
+        }
+
+        Ok(Class {
+            name: class_identifier.value,
+            var_decs,
+            constants,
+            enums,
+            subroutines,
+            span: span_of(&class_tok),
+        })
+    }
+
+    /// `const type name '=' expression ';'`
+    fn compile_const_dec(&mut self) -> Result<ConstDec, Diagnostic> {
+        let const_tok = self.expect_value("const")?;
+        let type_tok = self.expect_type()?;
+        let name_tok = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+        self.expect_value("=")?;
+        let value = self.compile_expression()?;
+        self.expect_value(";")?;
+
+        Ok(ConstDec {
+            type_name: type_tok.value,
+            name: name_tok.value,
+            value,
+            span: span_of(&const_tok),
+        })
+    }
+
+    /// `enum name '{' member (',' member)* '}'` where a member is `name ('=' expression)?`.
+    fn compile_enum_dec(&mut self) -> Result<EnumDec, Diagnostic> {
+        let enum_tok = self.expect_value("enum")?;
+        let name_tok = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+        self.expect_value("{")?;
+
+        let mut members = Vec::new();
+        loop {
+            let member_tok = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+            let value = if self.peek_is("=") {
+                self.advance();
+                Some(self.compile_expression()?)
+            } else {
+                None
+            };
+            members.push(EnumMember {
+                name: member_tok.value.clone(),
+                value,
+                span: span_of(&member_tok),
+            });
+            if self.peek_is(",") {
+                self.advance();
+            } else if self.peek_is("}") {
+                self.advance();
+                break;
+            } else {
+                return Err(self.unexpected(&["','", "'}'"]));
+            }
+        }
+
+        Ok(EnumDec {
+            name: name_tok.value,
+            members,
+            span: span_of(&enum_tok),
+        })
+    }
+
+    fn compile_class_var_dec(&mut self) -> Result<ClassVarDec, Diagnostic> {
+        let kind_tok = self.expect_kind(TOKEN_TYPE::KEYWORD)?;
+        let kind = if kind_tok.value == "static" {
+            ClassVarKind::Static
+        } else {
+            ClassVarKind::Field
+        };
+
+        // Second token is the type
+        let var_type = self.expect_type()?;
+        let names = self.compile_name_list()?;
+
+        Ok(ClassVarDec {
+            kind,
+            type_name: var_type.value,
+            names,
+            span: span_of(&kind_tok),
+        })
+    }
+
+    /// `varName (',' varName)* ';'`, shared by class and local variable declarations.
+    fn compile_name_list(&mut self) -> Result<Vec<(String, Span)>, Diagnostic> {
+        let mut names = Vec::new();
+        loop {
+            let name = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+            names.push((name.value.clone(), span_of(&name)));
+            if self.peek_is(",") {
+                self.advance();
+            } else if self.peek_is(";") {
+                self.advance();
+                break;
+            } else {
+                return Err(self.unexpected(&["','", "';'"]));
+            }
+        }
+        Ok(names)
+    }
+
+    fn compile_subroutine(&mut self) -> Result<SubroutineDec, Diagnostic> {
+        let subroutine_kind = self.expect_kind(TOKEN_TYPE::KEYWORD)?;
+        let kind = match subroutine_kind.value.as_str() {
+            "constructor" => SubroutineKind::Constructor,
+            "method" => SubroutineKind::Method,
+            _ => SubroutineKind::Function,
+        };
+
+        let return_type = self.expect_return_type()?;
+        let f_name = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?; // f_name
+
+        self.expect_value("(")?;
+        let parameters = self.compile_parameter_list()?;
+        self.expect_value(")")?;
+
+        self.expect_value("{")?;
+        let var_decs = self.compile_var_dec()?;
+        let statements = self.compile_statements();
+        self.expect_value("}")?;
+
+        Ok(SubroutineDec {
+            kind,
+            return_type: return_type.value,
+            name: f_name.value,
+            parameters,
+            var_decs,
+            statements,
+            span: span_of(&subroutine_kind),
+        })
+    }
+
+    fn compile_parameter_list(&mut self) -> Result<Vec<Parameter>, Diagnostic> {
+        let mut parameters = Vec::new();
+        if self.peek_is(")") {
+            return Ok(parameters);
+        }
+        loop {
+            let t_token = self.expect_type()?;
+            let n_token = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+            parameters.push(Parameter {
+                type_name: t_token.value.clone(),
+                name: n_token.value,
+                span: span_of(&t_token),
+            });
+            if self.peek_is(",") {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        Ok(parameters)
+    }
+
+    fn compile_var_dec(&mut self) -> Result<Vec<VarDec>, Diagnostic> {
+        let mut var_decs = Vec::new();
+
+        while self.peek_is("var") {
+            let var_token = self.expect_value("var")?;
+            let type_token = self.expect_type()?;
+            // here its: varName , otherVarName , varName2 , lastVarName ;
+            let names = self.compile_name_list()?;
+            var_decs.push(VarDec {
+                type_name: type_token.value,
+                names,
+                span: span_of(&var_token),
+            });
+        }
+
+        Ok(var_decs)
+    }
+
+    /// Statements up to the closing `}`. A statement with a syntax error is recorded in
+    /// `errors` and skipped.
+    fn compile_statements(&mut self) -> Vec<Statement> {
+        let mut statements = Vec::new();
+        while let Some(tok) = self.peek() {
+            if tok.value == "}" {
+                break;
+            }
+            let start = self.pos;
+            let statement = match tok.value.as_str() {
+                "let" => self.compile_let(),
+                "if" => self.compile_if(),
+                "do" => self.compile_do(),
+                "while" => self.compile_while(),
+                "return" => self.compile_return(),
+                "for" if tok.kind == TOKEN_TYPE::KEYWORD => self.compile_for(),
+                "break" | "continue" if tok.kind == TOKEN_TYPE::KEYWORD => {
+                    self.compile_loop_control(&tok.value)
+                }
+                _ if self.extensions => Err(self.unexpected(&[
+                    "'let'",
+                    "'if'",
+                    "'while'",
+                    "'do'",
+                    "'return'",
+                    "'for'",
+                    "'break'",
+                    "'continue'",
+                    "'}'",
+                ])),
+                _ => Err(self.unexpected(&["'let'", "'if'", "'while'", "'do'", "'return'", "'}'"])),
+            };
+            match statement {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    self.errors.push(error);
+                    self.synchronize(start);
+                }
+            }
+        }
+        statements
+    }
+
+    /// Skips to the start of the next statement: past the next `;`, or up to a statement
+    /// keyword or the `}` closing the current block. Nested blocks are skipped whole.
+    fn synchronize(&mut self, start: usize) {
+        if self.pos == start {
+            self.pos += 1;
+        }
+        let mut depth = 0;
+        while let Some(tok) = self.tokens.get(self.pos) {
+            match tok.value.as_str() {
+                "{" => depth += 1,
+                "}" if depth == 0 => return,
+                "}" => depth -= 1,
+                ";" if depth == 0 => {
+                    self.pos += 1;
+                    return;
+                }
+                keyword
+                    if depth == 0
+                        && tok.kind == TOKEN_TYPE::KEYWORD
+                        && STATEMENT_KEYWORDS.contains(&keyword) =>
+                {
+                    return;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn compile_let(&mut self) -> Result<Statement, Diagnostic> {
+        let statement = self.compile_assignment()?;
+        self.expect_value(";")?;
+        Ok(statement)
+    }
+
+    /// `let varName ('[' expression ']')? '=' expression`, without the `;`.
+    fn compile_assignment(&mut self) -> Result<Statement, Diagnostic> {
+        let let_tok = self.expect_value("let")?;
+        let varname_tok = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+
+        let index = if self.peek_is("[") {
+            // we are an array
+            self.advance();
+            let index = self.compile_expression()?;
+            self.expect_value("]")?;
+            Some(index)
+        } else {
+            None
+        };
+
+        if !self.peek_is("=") {
+            let expected: &[&str] = if index.is_some() {
+                &["'='"]
+            } else {
+                &["'['", "'='"]
+            };
+            return Err(self.unexpected(expected));
+        }
+        self.advance();
+        let value = self.compile_expression()?;
+
+        Ok(Statement {
+            kind: StatementKind::Let {
+                name: varname_tok.value,
+                index,
+                value,
+            },
+            span: span_of(&let_tok),
+        })
+    }
+
+    /// `'{' statements '}'`
+    fn compile_block(&mut self) -> Result<Vec<Statement>, Diagnostic> {
+        self.expect_value("{")?;
+        let statements = self.compile_statements();
+        self.expect_value("}")?;
+        Ok(statements)
+    }
+
+    fn compile_if(&mut self) -> Result<Statement, Diagnostic> {
+        let if_tok = self.expect_value("if")?;
+        self.expect_value("(")?;
+        let condition = self.compile_expression()?;
+        self.expect_value(")")?;
+
+        let then_branch = self.compile_block()?;
+        let else_branch = if self.peek_is("else") {
+            self.advance();
+            if self.extensions && self.peek_is("if") {
+                // `else if` is `else { if ... }`
+                Some(vec![self.compile_if()?])
+            } else {
+                Some(self.compile_block()?)
+            }
+        } else {
+            None
+        };
+
+        Ok(Statement {
+            kind: StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            },
+            span: span_of(&if_tok),
+        })
+    }
+
+    fn compile_while(&mut self) -> Result<Statement, Diagnostic> {
+        let while_tok = self.expect_value("while")?;
+        self.expect_value("(")?;
+        let condition = self.compile_expression()?;
+        self.expect_value(")")?;
+        let body = self.compile_loop_body()?;
+
+        Ok(Statement {
+            kind: StatementKind::While { condition, body },
+            span: span_of(&while_tok),
+        })
+    }
+
+    /// A loop's block, in which `break` and `continue` are allowed.
+    fn compile_loop_body(&mut self) -> Result<Vec<Statement>, Diagnostic> {
+        self.loop_depth += 1;
+        let body = self.compile_block();
+        self.loop_depth -= 1;
+        body
+    }
+
+    /// `for '(' simpleStatement ';' expression ';' simpleStatement ')' '{' statements '}'`
+    fn compile_for(&mut self) -> Result<Statement, Diagnostic> {
+        let for_tok = self.expect_value("for")?;
+        self.expect_value("(")?;
+        let init = self.compile_simple_statement()?;
+        self.expect_value(";")?;
+        let condition = self.compile_expression()?;
+        self.expect_value(";")?;
+        let step = self.compile_simple_statement()?;
+        self.expect_value(")")?;
+        let body = self.compile_loop_body()?;
+
+        Ok(Statement {
+            kind: StatementKind::For {
+                init: Box::new(init),
+                condition,
+                step: Box::new(step),
+                body,
+            },
+            span: span_of(&for_tok),
+        })
+    }
+
+    /// The `let` or `do` statement, without its `;`, that starts or steps a `for` loop.
+    fn compile_simple_statement(&mut self) -> Result<Statement, Diagnostic> {
+        if self.peek_is("let") {
+            self.compile_assignment()
+        } else if self.peek_is("do") {
+            self.compile_call_statement()
+        } else {
+            Err(self.unexpected(&["'let'", "'do'"]))
+        }
+    }
+
+    /// `break ';'` or `continue ';'`, which must be inside a loop.
+    fn compile_loop_control(&mut self, keyword: &str) -> Result<Statement, Diagnostic> {
+        let tok = self.expect_value(keyword)?;
+        self.expect_value(";")?;
+        if self.loop_depth == 0 {
+            return Err(Diagnostic::error(
+                span_of(&tok),
+                format!("'{}' outside of a loop", keyword),
+            ));
+        }
+        let kind = if keyword == "break" {
+            StatementKind::Break
+        } else {
+            StatementKind::Continue
+        };
+        Ok(Statement {
+            kind,
+            span: span_of(&tok),
+        })
+    }
+
+    fn compile_return(&mut self) -> Result<Statement, Diagnostic> {
+        let return_tok = self.expect_value("return")?;
+        let value = if self.peek_is(";") {
+            None
+        } else {
+            Some(self.compile_expression()?)
+        };
+        self.expect_value(";")?;
+
+        Ok(Statement {
+            kind: StatementKind::Return(value),
+            span: span_of(&return_tok),
+        })
+    }
+
+    fn compile_do(&mut self) -> Result<Statement, Diagnostic> {
+        let statement = self.compile_call_statement()?;
+        self.expect_value(";")?;
+        Ok(statement)
+    }
+
+    /// `do subroutineCall`, without the `;`.
+    fn compile_call_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let do_tok = self.expect_value("do")?;
+        let name_tok = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+        let call = self.compile_subroutine_call(name_tok)?;
+
+        Ok(Statement {
+            kind: StatementKind::Do(call),
+            span: span_of(&do_tok),
+        })
+    }
+
+    /// `subroutineName '(' expressionList ')'` or `(className | varName) '.' subroutineName
+    /// '(' expressionList ')'`, with the first identifier already consumed.
+    fn compile_subroutine_call(&mut self, first_tok: Token) -> Result<SubroutineCall, Diagnostic> {
+        let (receiver, name) = if self.peek_is(".") {
+            self.advance();
+            let subroutine_name_token = self.expect_kind(TOKEN_TYPE::IDENTIFIER)?;
+            (Some(first_tok.value.clone()), subroutine_name_token.value)
+        } else if self.peek_is("(") {
+            (None, first_tok.value.clone())
+        } else {
+            return Err(self.unexpected(&["'('", "'.'"]));
+        };
+
+        self.expect_value("(")?;
+        let args = self.compile_expression_list()?;
+        self.expect_value(")")?;
+
+        Ok(SubroutineCall {
+            receiver,
+            name,
+            args,
+            span: span_of(&first_tok),
+        })
+    }
+
+    pub fn compile_expression(&mut self) -> Result<Expression, Diagnostic> {
+        let first = Expression::Term(self.compile_term()?);
+        let mut rest = Vec::new();
+        while let Some(op) = self.peek_operator() {
+            let operator_tok = self.advance().unwrap();
+            let term = Expression::Term(self.compile_term()?);
+            rest.push((op, span_of(&operator_tok), term));
+        }
+        self.check_grouping(&rest);
+
+        let mut chain = rest.into_iter().peekable();
+        if self.precedence {
+            Ok(climb(first, &mut chain, 0))
+        } else {
+            Ok(chain.fold(first, |lhs, (op, span, rhs)| binary(op, span, lhs, rhs)))
+        }
+    }
+
+    /// Warns when the two ways of grouping an operator chain give different trees, which
+    /// happens exactly where an operator binds tighter than the one before it.
+    fn check_grouping(&mut self, rest: &[(BinaryOp, Span, Expression)]) {
+        let Some(pair) = rest
+            .windows(2)
+            .find(|pair| pair[1].0.precedence() > pair[0].0.precedence())
+        else {
+            return;
+        };
+        let (before, (op, span, _)) = (pair[0].0.symbol(), &pair[1]);
+        let message = if self.precedence {
+            format!(
+                "'{}' is applied before '{}' here because of --precedence; standard Jack applies operators left to right",
+                op.symbol(),
+                before
+            )
+        } else {
+            format!(
+                "'{}' is applied after '{}' here because Jack applies operators left to right; with --precedence this expression has a different value",
+                op.symbol(),
+                before
+            )
+        };
+        self.warnings.push(Diagnostic::warning(*span, message));
+    }
+
+    fn compile_term(&mut self) -> Result<Term, Diagnostic> {
+        let Some(first_tok) = self.peek() else {
+            return Err(self.unexpected(&TERM_START));
+        };
+        let span = span_of(&first_tok);
+
+        let kind = match first_tok.kind {
+            TOKEN_TYPE::IDENTIFIER => {
+                self.advance();
+                if self.peek_is("[") {
+                    // let a[b[a[3]]] = a[a[5]] * b[((7 - a[3]) - Main.double(2)) + 1];
+                    self.advance();
+                    let index = self.compile_expression()?;
+                    self.expect_value("]")?;
+                    TermKind::ArrayAccess {
+                        name: first_tok.value,
+                        index: Box::new(index),
+                    }
+                } else if self.peek_is("(") || self.peek_is(".") {
+                    TermKind::Call(self.compile_subroutine_call(first_tok)?)
+                } else {
+                    TermKind::VarName(first_tok.value)
+                }
+            }
+            TOKEN_TYPE::INT_CONST => {
+                let value = int_value(&first_tok.value).ok_or_else(|| {
+                    Diagnostic::error(
+                        span,
+                        format!("invalid integer constant {}", first_tok.value),
+                    )
+                })?;
+                self.advance();
+                TermKind::IntegerConstant(value)
+            }
+            TOKEN_TYPE::CHAR_CONST => {
+                // the tokenizer has already checked it is one Hack character
+                let code = first_tok.value.chars().next().and_then(hack_char_code);
+                self.advance();
+                TermKind::IntegerConstant(code.unwrap_or_default())
+            }
+            TOKEN_TYPE::STRING_CONST => {
+                self.advance();
+                TermKind::StringConstant(first_tok.value)
+            }
+            TOKEN_TYPE::SYMBOL if first_tok.value == "(" => {
+                self.advance();
+                let expression = self.compile_expression()?;
+                self.expect_value(")")?;
+                TermKind::Paren(Box::new(expression))
+            }
+            TOKEN_TYPE::SYMBOL if first_tok.value == "-" || first_tok.value == "~" => {
+                self.advance();
+                let op = if first_tok.value == "-" {
+                    UnaryOp::Neg
+                } else {
+                    UnaryOp::Not
+                };
+                TermKind::Unary {
+                    op,
+                    term: Box::new(self.compile_term()?),
+                }
+            }
+            TOKEN_TYPE::KEYWORD => {
+                let constant = match first_tok.value.as_str() {
+                    "true" => KeywordConstant::True,
+                    "false" => KeywordConstant::False,
+                    "null" => KeywordConstant::Null,
+                    "this" => KeywordConstant::This,
+                    _ => return Err(self.unexpected(&TERM_START)),
+                };
+                self.advance();
+                TermKind::KeywordConstant(constant)
+            }
+            TOKEN_TYPE::SYMBOL | TOKEN_TYPE::COMMENT => return Err(self.unexpected(&TERM_START)),
+        };
+
+        Ok(Term { kind, span })
+    }
+
+    fn compile_expression_list(&mut self) -> Result<Vec<Expression>, Diagnostic> {
+        let mut args = Vec::new();
+
+        if !self.peek_is(")") {
+            args.push(self.compile_expression()?);
+            while self.peek_is(",") {
+                self.advance();
+                args.push(self.compile_expression()?);
+            }
+        }
+        Ok(args)
+    }
+}
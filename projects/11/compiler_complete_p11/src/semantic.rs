@@ -0,0 +1,293 @@
+use crate::ast::{
+    Class, Expression, KeywordConstant, Span, Statement, StatementKind, SubroutineCall,
+    SubroutineDec, SubroutineKind, Term, TermKind,
+};
+use crate::class_index::ClassIndex;
+use crate::diagnostic::Diagnostic;
+use crate::fold::{constant_value, define_constants};
+use crate::symbol_table::{Identifier_Kind, symbol_table};
+
+/// Checks one class against the index: undefined subroutines, argument counts, methods
+/// called without an object (and functions called with one), `this` and fields used inside
+/// functions, undefined variables, and non-void subroutines that can end without `return`.
+/// Calls into classes the index does not know are not checked.
+pub fn check(class: &Class, index: &ClassIndex) -> Vec<Diagnostic> {
+    let mut checker = SemanticChecker {
+        index,
+        symbol_table: symbol_table::new(),
+        class_name: String::new(),
+        subroutine_kind: SubroutineKind::Function,
+        errors: Vec::new(),
+    };
+    checker.check_class(class);
+    checker.errors
+}
+
+struct SemanticChecker<'a> {
+    index: &'a ClassIndex,
+    symbol_table: symbol_table,
+    class_name: String,
+    subroutine_kind: SubroutineKind,
+    errors: Vec<Diagnostic>,
+}
+
+/// Whether control can never reach the end of `statements`.
+pub fn always_returns(statements: &[Statement]) -> bool {
+    match statements.last().map(|s| &s.kind) {
+        Some(StatementKind::Return(_)) => true,
+        Some(StatementKind::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        }) => always_returns(then_branch) && always_returns(else_branch),
+        // `while (true)` only ends through a `break`
+        Some(StatementKind::While { condition, body })
+        | Some(StatementKind::For {
+            condition, body, ..
+        }) => constant_value(condition) == Some(-1) && !breaks(body),
+        _ => false,
+    }
+}
+
+/// Whether `statements` can `break` out of the loop they are the body of; a `break` in a
+/// nested loop leaves only that loop.
+fn breaks(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match &statement.kind {
+        StatementKind::Break => true,
+        StatementKind::If {
+            then_branch,
+            else_branch,
+            ..
+        } => breaks(then_branch) || else_branch.as_deref().is_some_and(breaks),
+        _ => false,
+    })
+}
+
+impl<'a> SemanticChecker<'a> {
+    fn error(&mut self, span: Span, message: String) {
+        self.errors.push(Diagnostic::error(span, message));
+    }
+
+    fn check_class(&mut self, class: &Class) {
+        self.class_name = class.name.clone();
+        self.symbol_table = symbol_table::new();
+
+        for var_dec in &class.var_decs {
+            for (name, _) in &var_dec.names {
+                self.symbol_table
+                    .define(name, &var_dec.type_name, var_dec.kind.as_str());
+            }
+        }
+        let errors = define_constants(&mut self.symbol_table, class);
+        self.errors.extend(errors);
+        for subroutine in &class.subroutines {
+            self.check_subroutine(subroutine);
+        }
+    }
+
+    fn check_subroutine(&mut self, subroutine: &SubroutineDec) {
+        self.symbol_table.start_subroutine();
+        self.subroutine_kind = subroutine.kind;
+
+        for parameter in &subroutine.parameters {
+            self.symbol_table
+                .define(&parameter.name, &parameter.type_name, "argument");
+        }
+        for var_dec in &subroutine.var_decs {
+            for (name, _) in &var_dec.names {
+                self.symbol_table.define(name, &var_dec.type_name, "var");
+            }
+        }
+
+        self.check_statements(&subroutine.statements);
+
+        if subroutine.return_type != "void" && !always_returns(&subroutine.statements) {
+            self.error(
+                subroutine.span,
+                format!(
+                    "{} '{}' returns {} but can reach the end without a return",
+                    subroutine.kind.as_str(),
+                    subroutine.name,
+                    subroutine.return_type
+                ),
+            );
+        }
+    }
+
+    fn check_statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::Let { name, index, value } => {
+                self.check_variable(name, statement.span);
+                if let Some(Identifier_Kind::CONST(_)) = self.symbol_table.kind_of(name) {
+                    self.error(
+                        statement.span,
+                        format!("cannot assign to constant '{}'", name),
+                    );
+                }
+                if let Some(index) = index {
+                    self.check_expression(index);
+                }
+                self.check_expression(value);
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check_expression(condition);
+                self.check_statements(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.check_statements(else_branch);
+                }
+            }
+            StatementKind::While { condition, body } => {
+                self.check_expression(condition);
+                self.check_statements(body);
+            }
+            StatementKind::For {
+                init,
+                condition,
+                step,
+                body,
+            } => {
+                self.check_statement(init);
+                self.check_expression(condition);
+                self.check_statements(body);
+                self.check_statement(step);
+            }
+            StatementKind::Do(call) => self.check_call(call),
+            StatementKind::Return(value) => {
+                if let Some(value) = value {
+                    self.check_expression(value);
+                }
+            }
+            StatementKind::Break | StatementKind::Continue => {}
+        }
+    }
+
+    fn check_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Term(term) => self.check_term(term),
+            Expression::Binary { lhs, rhs, .. } => {
+                self.check_expression(lhs);
+                self.check_expression(rhs);
+            }
+        }
+    }
+
+    fn check_term(&mut self, term: &Term) {
+        match &term.kind {
+            TermKind::IntegerConstant(_) | TermKind::StringConstant(_) => {}
+            TermKind::KeywordConstant(KeywordConstant::This) => {
+                if self.subroutine_kind == SubroutineKind::Function {
+                    self.error(
+                        term.span,
+                        "'this' cannot be used inside a function".to_string(),
+                    );
+                }
+            }
+            TermKind::KeywordConstant(_) => {}
+            TermKind::VarName(name) => self.check_variable(name, term.span),
+            TermKind::ArrayAccess { name, index } => {
+                self.check_variable(name, term.span);
+                self.check_expression(index);
+            }
+            TermKind::Call(call) => self.check_call(call),
+            TermKind::Paren(expression) => self.check_expression(expression),
+            TermKind::Unary { term, .. } => self.check_term(term),
+        }
+    }
+
+    fn check_variable(&mut self, name: &str, span: Span) {
+        match self.symbol_table.kind_of(name) {
+            None => self.error(span, format!("undefined variable '{}'", name)),
+            Some(Identifier_Kind::FIELD) if self.subroutine_kind == SubroutineKind::Function => {
+                self.error(
+                    span,
+                    format!("field '{}' cannot be used inside a function", name),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn check_call(&mut self, call: &SubroutineCall) {
+        for arg in &call.args {
+            self.check_expression(arg);
+        }
+
+        if let Some(receiver) = &call.receiver
+            && self.symbol_table.kind_of(receiver).is_some()
+        {
+            self.check_variable(receiver, call.span);
+        }
+
+        let target = self
+            .index
+            .resolve_call(&self.class_name, &self.symbol_table, call);
+        let (target_class, on_object) = (target.class_name, target.on_object);
+        if !self.index.contains_class(&target_class) {
+            return;
+        }
+        let Some(subroutine) = target.subroutine else {
+            self.error(
+                call.span,
+                format!("undefined subroutine '{}.{}'", target_class, call.name),
+            );
+            return;
+        };
+
+        let is_method = subroutine.kind == SubroutineKind::Method;
+        if is_method && !on_object {
+            self.error(
+                call.span,
+                format!(
+                    "method '{}.{}' is called as a function; call it on an object",
+                    target_class, call.name
+                ),
+            );
+        } else if is_method
+            && call.receiver.is_none()
+            && self.subroutine_kind == SubroutineKind::Function
+        {
+            self.error(
+                call.span,
+                format!(
+                    "method '{}' is called on the current object, which a function does not have",
+                    call.name
+                ),
+            );
+        } else if !is_method && on_object {
+            self.error(
+                call.span,
+                format!(
+                    "{} '{}.{}' is called on an object; call it as {}.{}()",
+                    subroutine.kind.as_str(),
+                    target_class,
+                    call.name,
+                    target_class,
+                    call.name
+                ),
+            );
+        }
+
+        if subroutine.parameters.len() != call.args.len() {
+            self.error(
+                call.span,
+                format!(
+                    "'{}.{}' expects {} argument(s), got {}",
+                    target_class,
+                    call.name,
+                    subroutine.parameters.len(),
+                    call.args.len()
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,194 @@
+pub struct IdentifierEntry {
+    pub name: String,
+    pub type_name: String,
+    pub kind: Identifier_Kind,
+    pub index: usize,
+}
+
+pub enum Identifier_Kind {
+    STATIC, // scope class
+    FIELD,  // scope class
+    ARG,    // scope subroutine
+    VAR,    // scope subroutine
+    // scope class; a compile-time value that needs no segment slot
+    CONST(i16),
+}
+
+impl Identifier_Kind {
+    pub fn kind_to_segment(&self) -> &'static str {
+        match self {
+            Identifier_Kind::STATIC => "static",
+            Identifier_Kind::FIELD => "this",
+            Identifier_Kind::ARG => "argument",
+            Identifier_Kind::VAR => "local",
+            Identifier_Kind::CONST(_) => "constant",
+        }
+    }
+}
+pub struct symbol_table {
+    pub class_scope: Vec<IdentifierEntry>, // name, type, kind/segment, index
+    pub subroutine_scope: Vec<IdentifierEntry>,
+    pub static_index: usize,
+    pub field_index: usize,
+    pub arg_index: usize,
+    pub var_index: usize,
+}
+impl Default for symbol_table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl symbol_table {
+    pub fn new() -> Self {
+        Self {
+            class_scope: Vec::new(),
+            subroutine_scope: Vec::new(),
+            static_index: 0,
+            field_index: 0,
+            arg_index: 0,
+            var_index: 0,
+        }
+    }
+    pub fn start_subroutine(&mut self) {
+        // Starts a new subroutine scope = erases all names in the previous subroutine’s scope.
+        self.subroutine_scope.clear();
+        self.arg_index = 0;
+        self.var_index = 0;
+    }
+    pub fn define(&mut self, name: &str, ty: &str, kind: &str) {
+        // Defines a new identifier of a given name, type and kind and assigns it a running index. STATIC and FIELD identifiers
+        // have a class scope, while ARG and VAR identifiers have a subroutine scope.
+        let index = self.var_count(kind);
+        let identifier_kind = match kind {
+            "static" => Identifier_Kind::STATIC,
+            "field" => Identifier_Kind::FIELD,
+            "var" => Identifier_Kind::VAR,
+            "argument" => Identifier_Kind::ARG,
+            _ => panic!("Invalid identifier kind: {}", kind),
+        };
+        match identifier_kind {
+            Identifier_Kind::ARG => {
+                self.subroutine_scope.push(IdentifierEntry {
+                    name: name.to_string(),
+                    type_name: ty.to_string(),
+                    kind: identifier_kind,
+                    index,
+                });
+                self.arg_index += 1;
+            }
+            Identifier_Kind::VAR => {
+                self.subroutine_scope.push(IdentifierEntry {
+                    name: name.to_string(),
+                    type_name: ty.to_string(),
+                    kind: identifier_kind,
+                    index,
+                });
+                self.var_index += 1
+            }
+            Identifier_Kind::STATIC => {
+                self.class_scope.push(IdentifierEntry {
+                    name: name.to_string(),
+                    type_name: ty.to_string(),
+                    kind: identifier_kind,
+                    index,
+                });
+                self.static_index += 1
+            }
+            Identifier_Kind::FIELD => {
+                self.class_scope.push(IdentifierEntry {
+                    name: name.to_string(),
+                    type_name: ty.to_string(),
+                    kind: identifier_kind,
+                    index,
+                });
+                self.field_index += 1
+            }
+            Identifier_Kind::CONST(_) => unreachable!("constants are defined with define_constant"),
+        }
+    }
+
+    pub fn define_constant(&mut self, name: &str, ty: &str, value: i16) {
+        // Constants have a class scope but no index: every use is replaced by the value.
+        self.class_scope.push(IdentifierEntry {
+            name: name.to_string(),
+            type_name: ty.to_string(),
+            kind: Identifier_Kind::CONST(value),
+            index: 0,
+        });
+    }
+
+    pub fn constant_value(&self, name: &str) -> Option<i16> {
+        match self.kind_of(name)? {
+            Identifier_Kind::CONST(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn var_count(&self, kind: &str) -> usize {
+        // Returns the number of variables of the given kind already defined in the current scope.
+        let identifier_kind = match kind {
+            "static" => Identifier_Kind::STATIC,
+            "field" => Identifier_Kind::FIELD,
+            "var" => Identifier_Kind::VAR,
+            "argument" => Identifier_Kind::ARG,
+            _ => panic!("Invalid identifier kind: {}", kind),
+        };
+        match identifier_kind {
+            Identifier_Kind::ARG => self.arg_index,
+            Identifier_Kind::VAR => self.var_index,
+            Identifier_Kind::FIELD => self.field_index,
+            Identifier_Kind::STATIC => self.static_index,
+            Identifier_Kind::CONST(_) => 0,
+        }
+    }
+    pub fn entry(&self, name: &str) -> Option<&IdentifierEntry> {
+        // Returns the whole entry of the named identifier, subroutine scope first.
+        self.subroutine_scope
+            .iter()
+            .find(|x| x.name == name)
+            .or_else(|| self.class_scope.iter().find(|x| x.name == name))
+    }
+    pub fn kind_of(&self, name: &str) -> Option<&Identifier_Kind> {
+        // Returns the kind of the named identifier in the current scope. If the identifier is unknown in the current scope, returns NONE.
+        // check if its in subroutine first
+        self.subroutine_scope
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| &x.kind)
+            .or_else(|| {
+                self.class_scope
+                    .iter()
+                    .find(|x| x.name == name)
+                    .map(|x| &x.kind)
+            })
+    }
+    pub fn type_of(&self, name: &str) -> Option<&String> {
+        self.subroutine_scope
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| &x.type_name)
+            .or_else(|| {
+                self.class_scope
+                    .iter()
+                    .find(|x| x.name == name)
+                    .map(|x| &x.type_name)
+            })
+    }
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.subroutine_scope
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| x.index)
+            .or_else(|| {
+                self.class_scope
+                    .iter()
+                    .find(|x| x.name == name)
+                    .map(|x| x.index)
+            })
+        // Returns the index assigned to the named identifier.
+    }
+    pub fn clear_subroutine_scope(&mut self) {
+        self.subroutine_scope.clear();
+    }
+}
@@ -0,0 +1,435 @@
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+use crate::ast::Span;
+use crate::diagnostic::Diagnostic;
+use crate::xml_writer;
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum TOKEN_TYPE {
+    KEYWORD,
+    SYMBOL,
+    IDENTIFIER,
+    INT_CONST,
+    STRING_CONST,
+    // `'A'`, only with `--extensions`; the value is the character without its quotes
+    CHAR_CONST,
+    // `// ...`, `/* ... */` or `/** ... */` with its delimiters, only from `with_comments`
+    COMMENT,
+}
+
+impl TOKEN_TYPE {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TOKEN_TYPE::KEYWORD => "keyword",
+            TOKEN_TYPE::SYMBOL => "symbol",
+            TOKEN_TYPE::IDENTIFIER => "identifier",
+            TOKEN_TYPE::INT_CONST => "integerConstant",
+            TOKEN_TYPE::STRING_CONST => "stringConstant",
+            TOKEN_TYPE::CHAR_CONST => "charConstant",
+            TOKEN_TYPE::COMMENT => "comment",
+        }
+    }
+
+    /// How the kind reads in an error message.
+    pub fn description(&self) -> &'static str {
+        match self {
+            TOKEN_TYPE::KEYWORD => "keyword",
+            TOKEN_TYPE::SYMBOL => "symbol",
+            TOKEN_TYPE::IDENTIFIER => "identifier",
+            TOKEN_TYPE::INT_CONST => "integer constant",
+            TOKEN_TYPE::STRING_CONST => "string constant",
+            TOKEN_TYPE::CHAR_CONST => "character constant",
+            TOKEN_TYPE::COMMENT => "comment",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Token {
+    pub value: String,
+    pub kind: TOKEN_TYPE,
+    // 1-based position of the token's first character
+    pub line: usize,
+    pub column: usize,
+}
+
+pub const KEYWORDS: [&str; 21] = [
+    "class",
+    "constructor",
+    "function",
+    "method",
+    "field",
+    "static",
+    "var",
+    "int",
+    "char",
+    "boolean",
+    "void",
+    "true",
+    "false",
+    "null",
+    "this",
+    "let",
+    "do",
+    "if",
+    "else",
+    "while",
+    "return",
+];
+
+/// Keywords of the `--extensions` language; in standard Jack these are identifiers.
+pub const EXTENSION_KEYWORDS: [&str; 5] = ["for", "break", "continue", "const", "enum"];
+
+pub const SYMBOLS: [char; 19] = [
+    '{', '}', '(', ')', '[', ']', '.', ',', ';', '+', '-', '*', '/', '&', '|', '<', '>', '=', '~',
+];
+
+/// Largest integer constant the Jack language allows.
+pub const MAX_INT_CONST: u32 = 32767;
+
+/// The code of `c` in the Hack character set, which shares the printable ASCII range.
+/// Anything else (tabs, non-ASCII letters, ...) cannot be displayed and has no code.
+pub fn hack_char_code(c: char) -> Option<u16> {
+    match c {
+        ' '..='~' => Some(c as u16),
+        _ => None,
+    }
+}
+
+/// The value of an integer constant token: decimal, or hexadecimal like `0x4000` (which
+/// the tokenizer only produces with `--extensions`).
+pub fn int_value(token: &str) -> Option<u16> {
+    match token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+    {
+        Some(digits) => u16::from_str_radix(digits, 16).ok(),
+        None => token.parse().ok(),
+    }
+}
+
+/// Walks the source one character at a time, keeping track of the current line and column.
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    extensions: bool,
+    // return comments as tokens instead of skipping them
+    keep_comments: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str, extensions: bool, keep_comments: bool) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+            line: 1,
+            column: 1,
+            extensions,
+            keep_comments,
+        }
+    }
+
+    fn at_comment(&mut self) -> bool {
+        self.peek() == Some('/') && matches!(self.peek_second(), Some('/' | '*'))
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        let mut ahead = self.chars.clone();
+        ahead.next();
+        ahead.next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(line: usize, column: usize, message: String) -> Diagnostic {
+        Diagnostic::error(Span { line, column }, message)
+    }
+
+    /// Skips whitespace and, unless they are kept, all three comment forms: `// ...`,
+    /// `/* ... */` and `/** ... */`.
+    fn skip_trivia(&mut self) -> Result<(), Diagnostic> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') if self.at_comment() && !self.keep_comments => {
+                    self.comment()?;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Consumes the comment starting here and returns its text, delimiters included.
+    fn comment(&mut self) -> Result<String, Diagnostic> {
+        let (line, column) = (self.line, self.column);
+        let mut text = String::new();
+        if self.peek_second() == Some('/') {
+            while let Some(c) = self.peek().filter(|c| *c != '\n') {
+                text.push(c);
+                self.bump();
+            }
+            // a `\r` from a Windows line ending is not part of the comment
+            text.truncate(text.trim_end().len());
+            return Ok(text);
+        }
+
+        text.extend(self.bump());
+        text.extend(self.bump());
+        loop {
+            match self.bump() {
+                Some('*') if self.peek() == Some('/') => {
+                    self.bump();
+                    text.push_str("*/");
+                    return Ok(text);
+                }
+                Some(c) => text.push(c),
+                None => {
+                    return Err(Self::error(
+                        line,
+                        column,
+                        "unterminated block comment".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, Diagnostic> {
+        self.skip_trivia()?;
+
+        let (line, column) = (self.line, self.column);
+        let Some(c) = self.peek() else {
+            return Ok(None);
+        };
+
+        let (value, kind) = if c == '/' && self.at_comment() {
+            (self.comment()?, TOKEN_TYPE::COMMENT)
+        } else if c == '"' {
+            // Jack strings have no escape sequences; everything up to the closing quote,
+            // including `//` and `/*`, is part of the literal. The quotes are not.
+            let mut value = String::new();
+            self.bump();
+            loop {
+                let (char_line, char_column) = (self.line, self.column);
+                match self.bump() {
+                    Some('"') => break,
+                    Some('\n') | None => {
+                        return Err(Self::error(
+                            line,
+                            column,
+                            "unterminated string constant".to_string(),
+                        ));
+                    }
+                    Some(ch) if hack_char_code(ch).is_none() => {
+                        return Err(Self::error(
+                            char_line,
+                            char_column,
+                            format!(
+                                "character {:?} (U+{:04X}) is not in the Hack character set",
+                                ch, ch as u32
+                            ),
+                        ));
+                    }
+                    Some(ch) => value.push(ch),
+                }
+            }
+            (value, TOKEN_TYPE::STRING_CONST)
+        } else if c == '\'' && self.extensions {
+            self.bump();
+            let (char_line, char_column) = (self.line, self.column);
+            let value = match self.bump() {
+                Some('\n') | None => {
+                    return Err(Self::error(
+                        line,
+                        column,
+                        "unterminated character constant".to_string(),
+                    ));
+                }
+                Some('\'') if self.peek() != Some('\'') => {
+                    return Err(Self::error(
+                        line,
+                        column,
+                        "empty character constant".to_string(),
+                    ));
+                }
+                Some(ch) if hack_char_code(ch).is_none() => {
+                    return Err(Self::error(
+                        char_line,
+                        char_column,
+                        format!(
+                            "character {:?} (U+{:04X}) is not in the Hack character set",
+                            ch, ch as u32
+                        ),
+                    ));
+                }
+                Some(ch) => ch,
+            };
+            if self.bump() != Some('\'') {
+                return Err(Self::error(
+                    line,
+                    column,
+                    "character constant must hold exactly one character".to_string(),
+                ));
+            }
+            (value.to_string(), TOKEN_TYPE::CHAR_CONST)
+        } else if c.is_ascii_digit() {
+            let mut value = String::new();
+            while let Some(ch) = self
+                .peek()
+                .filter(|ch| ch.is_ascii_alphanumeric() || *ch == '_')
+            {
+                value.push(ch);
+                self.bump();
+            }
+            let parsed = match value
+                .strip_prefix("0x")
+                .or_else(|| value.strip_prefix("0X"))
+            {
+                Some(digits) if self.extensions => u32::from_str_radix(digits, 16),
+                _ => value.parse::<u32>(),
+            };
+            match parsed {
+                Ok(n) if n <= MAX_INT_CONST => {}
+                Ok(_) => {
+                    return Err(Self::error(
+                        line,
+                        column,
+                        format!("integer constant {value} is larger than {MAX_INT_CONST}"),
+                    ));
+                }
+                Err(_) => {
+                    return Err(Self::error(
+                        line,
+                        column,
+                        format!("invalid integer constant '{value}'"),
+                    ));
+                }
+            }
+            (value, TOKEN_TYPE::INT_CONST)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut value = String::new();
+            while let Some(ch) = self
+                .peek()
+                .filter(|ch| ch.is_ascii_alphanumeric() || *ch == '_')
+            {
+                value.push(ch);
+                self.bump();
+            }
+            let kind = if KEYWORDS.contains(&value.as_str())
+                || (self.extensions && EXTENSION_KEYWORDS.contains(&value.as_str()))
+            {
+                TOKEN_TYPE::KEYWORD
+            } else {
+                TOKEN_TYPE::IDENTIFIER
+            };
+            (value, kind)
+        } else if SYMBOLS.contains(&c) {
+            self.bump();
+            (c.to_string(), TOKEN_TYPE::SYMBOL)
+        } else {
+            return Err(Self::error(
+                line,
+                column,
+                format!("unexpected character '{c}'"),
+            ));
+        };
+
+        Ok(Some(Token {
+            value,
+            kind,
+            line,
+            column,
+        }))
+    }
+}
+
+pub struct jack_tokenizer {
+    pub tokens: Vec<Token>,
+    current_token: Option<Token>,
+    pos: usize,
+}
+
+impl jack_tokenizer {
+    /// Tokenizes a whole file. With `extensions`, also accepts `EXTENSION_KEYWORDS`,
+    /// character constants and hexadecimal integers.
+    pub fn new(file: &str, extensions: bool) -> io::Result<Self> {
+        Self::tokenize(Lexer::new(file, extensions, false))
+    }
+
+    /// Like `new`, but keeps every comment as a `COMMENT` token, for tools that reprint
+    /// the source. The parser does not accept these tokens.
+    pub fn with_comments(file: &str, extensions: bool) -> io::Result<Self> {
+        Self::tokenize(Lexer::new(file, extensions, true))
+    }
+
+    fn tokenize(mut lexer: Lexer) -> io::Result<Self> {
+        let mut tokens: Vec<Token> = Vec::new();
+        while let Some(token) = lexer.next_token()? {
+            tokens.push(token);
+        }
+
+        Ok(Self {
+            tokens,
+            current_token: None,
+            pos: 0,
+        })
+    }
+
+    /// Writes every token in the course's `FooT.xml` format.
+    pub fn write_token_file(&self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(
+            OpenOptions::new()
+                .truncate(true)
+                .write(true)
+                .create(true)
+                .open(path)?,
+        );
+        writeln!(file, "<tokens>")?;
+        for token in &self.tokens {
+            let token_type = token.kind.as_str();
+            writeln!(
+                file,
+                "<{token_type}> {} </{token_type}>",
+                xml_writer::escape(&token.value)
+            )?;
+        }
+        writeln!(file, "</tokens>")?;
+        file.flush()
+    }
+
+    pub fn has_more_tokens(&self) -> bool {
+        self.pos < self.tokens.len()
+    }
+
+    pub fn advance(&mut self) {
+        if self.has_more_tokens() {
+            self.current_token = Some(self.tokens[self.pos].clone());
+            self.pos += 1;
+        } else {
+            self.current_token = None
+        }
+    }
+
+    pub fn token_type(&self) -> Option<TOKEN_TYPE> {
+        self.current_token.as_ref().map(|t| t.kind.clone())
+    }
+}
@@ -0,0 +1,279 @@
+use crate::ast::{
+    BinaryOp, Class, Expression, KeywordConstant, Span, Statement, StatementKind, SubroutineCall,
+    SubroutineDec, SubroutineKind, Term, TermKind, UnaryOp,
+};
+use crate::class_index::ClassIndex;
+use crate::diagnostic::Diagnostic;
+use crate::fold::define_constants;
+use crate::symbol_table::symbol_table;
+
+/// Type of `null`; it fits any variable.
+const NULL_TYPE: &str = "null";
+
+fn is_primitive(type_name: &str) -> bool {
+    matches!(type_name, "int" | "char" | "boolean")
+}
+
+/// Whether a value of type `actual` may be stored where `expected` is declared. `int`,
+/// `char` and `boolean` mix freely. `Array` is an untyped pointer, so it is interchangeable
+/// with `int`/`char` and with any object (`Memory.deAlloc(this)`, for instance).
+fn is_compatible(expected: &str, actual: &str) -> bool {
+    if expected == actual || actual == NULL_TYPE {
+        return true;
+    }
+    if is_primitive(expected) && is_primitive(actual) {
+        return true;
+    }
+    if expected == "Array" || actual == "Array" {
+        return expected != "boolean" && actual != "boolean";
+    }
+    false
+}
+
+/// Warns on assignments, arguments and arithmetic whose types do not fit, and on methods
+/// called on a variable of type `int`, `char` or `boolean`. Types that cannot be known
+/// (array elements, calls into classes the index does not know) are never warned about.
+/// Jack is loosely typed, so these are warnings and never stop compilation.
+pub fn check(class: &Class, index: &ClassIndex) -> Vec<Diagnostic> {
+    let mut checker = TypeChecker {
+        index,
+        symbol_table: symbol_table::new(),
+        class_name: String::new(),
+        warnings: Vec::new(),
+    };
+    checker.check_class(class);
+    checker.warnings
+}
+
+struct TypeChecker<'a> {
+    index: &'a ClassIndex,
+    symbol_table: symbol_table,
+    class_name: String,
+    warnings: Vec<Diagnostic>,
+}
+
+impl<'a> TypeChecker<'a> {
+    fn warn(&mut self, span: Span, message: String) {
+        self.warnings.push(Diagnostic::warning(span, message));
+    }
+
+    fn check_class(&mut self, class: &Class) {
+        self.class_name = class.name.clone();
+        self.symbol_table = symbol_table::new();
+
+        for var_dec in &class.var_decs {
+            for (name, _) in &var_dec.names {
+                self.symbol_table
+                    .define(name, &var_dec.type_name, var_dec.kind.as_str());
+            }
+        }
+        // errors in the values are reported by the semantic checks
+        define_constants(&mut self.symbol_table, class);
+        for subroutine in &class.subroutines {
+            self.check_subroutine(subroutine);
+        }
+    }
+
+    fn check_subroutine(&mut self, subroutine: &SubroutineDec) {
+        self.symbol_table.start_subroutine();
+        for parameter in &subroutine.parameters {
+            self.symbol_table
+                .define(&parameter.name, &parameter.type_name, "argument");
+        }
+        for var_dec in &subroutine.var_decs {
+            for (name, _) in &var_dec.names {
+                self.symbol_table.define(name, &var_dec.type_name, "var");
+            }
+        }
+        self.check_statements(&subroutine.statements);
+    }
+
+    fn check_statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::Let { name, index, value } => {
+                if let Some(index) = index {
+                    self.type_of_expression(index);
+                }
+                let actual = self.type_of_expression(value);
+                // array elements are untyped
+                if index.is_none()
+                    && let (Some(expected), Some(actual)) =
+                        (self.symbol_table.type_of(name).cloned(), actual)
+                    && !is_compatible(&expected, &actual)
+                {
+                    self.warn(
+                        value.span(),
+                        format!(
+                            "assigning a value of type {} to '{}', which is declared {}",
+                            actual, name, expected
+                        ),
+                    );
+                }
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.type_of_expression(condition);
+                self.check_statements(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.check_statements(else_branch);
+                }
+            }
+            StatementKind::While { condition, body } => {
+                self.type_of_expression(condition);
+                self.check_statements(body);
+            }
+            StatementKind::For {
+                init,
+                condition,
+                step,
+                body,
+            } => {
+                self.check_statement(init);
+                self.type_of_expression(condition);
+                self.check_statements(body);
+                self.check_statement(step);
+            }
+            StatementKind::Do(call) => {
+                self.type_of_call(call);
+            }
+            StatementKind::Return(value) => {
+                if let Some(value) = value {
+                    self.type_of_expression(value);
+                }
+            }
+            StatementKind::Break | StatementKind::Continue => {}
+        }
+    }
+
+    /// Warns when an operand of an `int` operator is an object other than an `Array`.
+    fn check_int_operand(&mut self, operand: Option<&str>, symbol: &str, span: Span) {
+        if let Some(operand) = operand
+            && operand != NULL_TYPE
+            && !is_primitive(operand)
+            && operand != "Array"
+        {
+            self.warn(
+                span,
+                format!("'{}' applied to an object of type {}", symbol, operand),
+            );
+        }
+    }
+
+    /// The static type of `expression`, or `None` when it cannot be known.
+    fn type_of_expression(&mut self, expression: &Expression) -> Option<String> {
+        match expression {
+            Expression::Term(term) => self.type_of_term(term),
+            Expression::Binary { op, lhs, rhs, span } => {
+                let lhs_type = self.type_of_expression(lhs);
+                let rhs_type = self.type_of_expression(rhs);
+                match op {
+                    BinaryOp::Eq => Some("boolean".to_string()),
+                    BinaryOp::Lt | BinaryOp::Gt => {
+                        self.check_int_operand(lhs_type.as_deref(), op.symbol(), lhs.span());
+                        self.check_int_operand(rhs_type.as_deref(), op.symbol(), rhs.span());
+                        Some("boolean".to_string())
+                    }
+                    _ => {
+                        self.check_int_operand(lhs_type.as_deref(), op.symbol(), *span);
+                        self.check_int_operand(rhs_type.as_deref(), op.symbol(), *span);
+                        match op {
+                            BinaryOp::And | BinaryOp::Or => lhs_type,
+                            _ => Some("int".to_string()),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn type_of_term(&mut self, term: &Term) -> Option<String> {
+        match &term.kind {
+            TermKind::IntegerConstant(_) => Some("int".to_string()),
+            TermKind::StringConstant(_) => Some("String".to_string()),
+            TermKind::KeywordConstant(constant) => match constant {
+                KeywordConstant::True | KeywordConstant::False => Some("boolean".to_string()),
+                KeywordConstant::Null => Some(NULL_TYPE.to_string()),
+                KeywordConstant::This => Some(self.class_name.clone()),
+            },
+            TermKind::VarName(name) => self.symbol_table.type_of(name).cloned(),
+            TermKind::ArrayAccess { index, .. } => {
+                self.type_of_expression(index);
+                None
+            }
+            TermKind::Call(call) => self.type_of_call(call),
+            TermKind::Paren(expression) => self.type_of_expression(expression),
+            TermKind::Unary { op, term } => {
+                let operand = self.type_of_term(term);
+                self.check_int_operand(operand.as_deref(), op.symbol(), term.span);
+                match op {
+                    UnaryOp::Neg => Some("int".to_string()),
+                    UnaryOp::Not => operand,
+                }
+            }
+        }
+    }
+
+    /// Checks the call's arguments and returns the callee's declared return type.
+    fn type_of_call(&mut self, call: &SubroutineCall) -> Option<String> {
+        let arg_types: Vec<Option<String>> = call
+            .args
+            .iter()
+            .map(|arg| self.type_of_expression(arg))
+            .collect();
+
+        let target = self
+            .index
+            .resolve_call(&self.class_name, &self.symbol_table, call);
+        let (target_class, on_object) = (target.class_name, target.on_object);
+
+        if on_object && is_primitive(&target_class) {
+            self.warn(
+                call.span,
+                format!(
+                    "calling '{}' on '{}', which is declared {}",
+                    call.name,
+                    call.receiver.as_deref().unwrap_or("this"),
+                    target_class
+                ),
+            );
+            return None;
+        }
+
+        if !self.index.contains_class(&target_class) {
+            return None;
+        }
+        // a subroutine the class does not have is already an error, see `semantic::check`
+        let subroutine = target.subroutine?;
+
+        for (((parameter_type, parameter_name), arg_type), arg) in
+            subroutine.parameters.iter().zip(&arg_types).zip(&call.args)
+        {
+            if let Some(arg_type) = arg_type
+                && !is_compatible(parameter_type, arg_type)
+            {
+                self.warn(
+                    arg.span(),
+                    format!(
+                        "argument '{}' of {}.{} is declared {}, got {}",
+                        parameter_name, target_class, call.name, parameter_type, arg_type
+                    ),
+                );
+            }
+        }
+
+        match subroutine.kind {
+            SubroutineKind::Constructor => Some(target_class),
+            _ if subroutine.return_type == "void" => None,
+            _ => Some(subroutine.return_type.clone()),
+        }
+    }
+}
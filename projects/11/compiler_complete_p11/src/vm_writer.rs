@@ -0,0 +1,146 @@
+use std::io;
+
+/// Collects the VM commands of one class in memory; `into_output` hands back the text of
+/// its `.vm` file.
+pub struct VM_Writer {
+    output: String,
+    // `--debug`: where every command came from, see `with_debug_map`
+    debug_map: Option<String>,
+    // lines written so far, and the Jack line the next commands come from
+    vm_line: usize,
+    source_line: usize,
+}
+
+impl Default for VM_Writer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VM_Writer {
+    pub fn new() -> Self {
+        Self {
+            output: String::new(),
+            debug_map: None,
+            vm_line: 0,
+            source_line: 0,
+        }
+    }
+
+    /// Also keeps a debug map for `vm_name` (the text of `Foo.vm.map`), which links every
+    /// command to the line of `source_name` it came from and names the arguments and
+    /// locals of every function. The format is explained at the top of the map.
+    pub fn with_debug_map(mut self, vm_name: &str, source_name: &str) -> Self {
+        self.debug_map = Some(format!(
+            "// Debug map for {vm} compiled from {source}. Each line is one of:
+//   source FILE                   the Jack file {vm} was compiled from
+//   function NAME                 the commands that follow are in function NAME
+//   argument|local INDEX TYPE NAME  the Jack variable kept in that slot of the function
+//   VMLINE JACKLINE               line VMLINE of {vm} came from line JACKLINE of FILE
+source {source}
+",
+            vm = vm_name,
+            source = source_name
+        ));
+        self
+    }
+
+    /// The VM code written, and the debug map if one was asked for.
+    pub fn into_output(self) -> (String, Option<String>) {
+        (self.output, self.debug_map)
+    }
+
+    pub fn source_line(&self) -> usize {
+        self.source_line
+    }
+
+    /// Sets the Jack line the commands written from now on are attributed to.
+    pub fn set_source_line(&mut self, line: usize) {
+        self.source_line = line;
+    }
+
+    /// Starts the debug map entries of function `name`; its variables follow, and then
+    /// its commands.
+    pub fn write_debug_function(&mut self, name: &str) -> io::Result<()> {
+        if let Some(map) = self.debug_map.as_mut() {
+            map.push_str(&format!("function {}\n", name));
+        }
+        Ok(())
+    }
+
+    /// Records in the debug map that `segment index` holds the Jack variable `name`.
+    pub fn write_variable(
+        &mut self,
+        segment: &str,
+        index: usize,
+        type_name: &str,
+        name: &str,
+    ) -> io::Result<()> {
+        if let Some(map) = self.debug_map.as_mut() {
+            map.push_str(&format!("{} {} {} {}\n", segment, index, type_name, name));
+        }
+        Ok(())
+    }
+    pub fn write_push(&mut self, segment: &str, index: usize) -> io::Result<()> {
+        let vm_to_write = format!("push {} {}\n", segment, index);
+        self.write_commands(vm_to_write)?;
+
+        Ok(())
+    }
+    pub fn write_pop(&mut self, segment: &str, index: usize) -> io::Result<()> {
+        let vm_to_write = format!("pop {} {}\n", segment, index);
+        self.write_commands(vm_to_write)?;
+
+        Ok(())
+    }
+    pub fn write_arithmetic(&mut self, command: &str) -> io::Result<()> {
+        let vm_to_write = format!("{}\n", command);
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    pub fn write_label(&mut self, label: &str) -> io::Result<()> {
+        let vm_to_write = format!("label {}\n", label);
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    pub fn write_goto(&mut self, label: &str) -> io::Result<()> {
+        let vm_to_write = format!("goto {}\n", label);
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    pub fn write_if(&mut self, label: &str) -> io::Result<()> {
+        let vm_to_write = format!("if-goto {}\n", label);
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    pub fn write_call(&mut self, name: &str, n_args: usize) -> io::Result<()> {
+        let vm_to_write = format!("call {} {}\n", name, n_args);
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    pub fn write_function(&mut self, name: &str, n_locals: usize) -> io::Result<()> {
+        let vm_to_write = format!("function {} {}\n", name, n_locals);
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    pub fn write_return(&mut self, with_expression: bool) -> io::Result<()> {
+        let vm_to_write = if with_expression {
+            "return\n".to_string()
+        } else {
+            // push dummy value
+            "push constant 0\nreturn\n".to_string()
+        };
+        self.write_commands(vm_to_write)?;
+        Ok(())
+    }
+    fn write_commands(&mut self, str_to_write: String) -> io::Result<()> {
+        if let Some(map) = self.debug_map.as_mut() {
+            for _ in str_to_write.lines() {
+                self.vm_line += 1;
+                map.push_str(&format!("{} {}\n", self.vm_line, self.source_line));
+            }
+        }
+        self.output.push_str(&str_to_write);
+        Ok(())
+    }
+}
@@ -0,0 +1,367 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::ast::{
+    Class, ClassVarDec, ConstDec, EnumDec, Expression, Statement, StatementKind, SubroutineCall,
+    SubroutineDec, Term, TermKind,
+};
+
+/// Writes the parse tree of a class in the course's XML format.
+pub struct XmlWriter {
+    file: BufWriter<File>,
+    indentation: usize,
+}
+
+fn type_tag(type_name: &str) -> &'static str {
+    match type_name {
+        "int" | "char" | "boolean" | "void" => "keyword",
+        _ => "identifier",
+    }
+}
+
+/// `text` with the characters XML reserves replaced by entities, so `<` is written as `&lt;`.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl XmlWriter {
+    pub fn new(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .truncate(true)
+            .write(true)
+            .create(true)
+            .open(path)?;
+
+        Ok(Self {
+            file: BufWriter::new(file),
+            indentation: 0,
+        })
+    }
+
+    fn write_open_tag(&mut self, s: &str) -> io::Result<()> {
+        let indentation = " ".repeat(self.indentation);
+        writeln!(self.file, "{indentation}<{s}>")?;
+        self.indentation += 2;
+        Ok(())
+    }
+
+    fn write_close_tag(&mut self, s: &str) -> io::Result<()> {
+        self.indentation -= 2;
+        let indentation = " ".repeat(self.indentation);
+        writeln!(self.file, "{indentation}</{s}>")
+    }
+
+    fn write_token(&mut self, token: &str, tag: &str) -> io::Result<()> {
+        let indentation = " ".repeat(self.indentation);
+        let token = escape(token);
+        writeln!(self.file, "{indentation}<{tag}> {token} </{tag}>")
+    }
+
+    fn write_type(&mut self, type_name: &str) -> io::Result<()> {
+        self.write_token(type_name, type_tag(type_name))
+    }
+
+    fn write_names(&mut self, names: &[(String, crate::ast::Span)]) -> io::Result<()> {
+        for (i, (name, _)) in names.iter().enumerate() {
+            if i > 0 {
+                self.write_token(",", "symbol")?;
+            }
+            self.write_token(name, "identifier")?;
+        }
+        self.write_token(";", "symbol")
+    }
+
+    pub fn write_class(&mut self, class: &Class) -> io::Result<()> {
+        self.write_open_tag("class")?;
+        self.write_token("class", "keyword")?;
+        self.write_token(&class.name, "identifier")?;
+        self.write_token("{", "symbol")?;
+
+        for var_dec in &class.var_decs {
+            self.write_class_var_dec(var_dec)?;
+        }
+        for constant in &class.constants {
+            self.write_const_dec(constant)?;
+        }
+        for enum_dec in &class.enums {
+            self.write_enum_dec(enum_dec)?;
+        }
+        for subroutine in &class.subroutines {
+            self.write_subroutine(subroutine)?;
+        }
+
+        self.write_token("}", "symbol")?;
+        self.write_close_tag("class")?;
+        self.file.flush()
+    }
+
+    fn write_class_var_dec(&mut self, var_dec: &ClassVarDec) -> io::Result<()> {
+        self.write_open_tag("classVarDec")?;
+        self.write_token(var_dec.kind.as_str(), "keyword")?;
+        self.write_type(&var_dec.type_name)?;
+        self.write_names(&var_dec.names)?;
+        self.write_close_tag("classVarDec")
+    }
+
+    fn write_const_dec(&mut self, constant: &ConstDec) -> io::Result<()> {
+        self.write_open_tag("classConstDec")?;
+        self.write_token("const", "keyword")?;
+        self.write_type(&constant.type_name)?;
+        self.write_token(&constant.name, "identifier")?;
+        self.write_token("=", "symbol")?;
+        self.write_expression(&constant.value)?;
+        self.write_token(";", "symbol")?;
+        self.write_close_tag("classConstDec")
+    }
+
+    fn write_enum_dec(&mut self, enum_dec: &EnumDec) -> io::Result<()> {
+        self.write_open_tag("enumDec")?;
+        self.write_token("enum", "keyword")?;
+        self.write_token(&enum_dec.name, "identifier")?;
+        self.write_token("{", "symbol")?;
+        for (i, member) in enum_dec.members.iter().enumerate() {
+            if i > 0 {
+                self.write_token(",", "symbol")?;
+            }
+            self.write_token(&member.name, "identifier")?;
+            if let Some(value) = &member.value {
+                self.write_token("=", "symbol")?;
+                self.write_expression(value)?;
+            }
+        }
+        self.write_token("}", "symbol")?;
+        self.write_close_tag("enumDec")
+    }
+
+    fn write_subroutine(&mut self, subroutine: &SubroutineDec) -> io::Result<()> {
+        self.write_open_tag("subroutineDec")?;
+        self.write_token(subroutine.kind.as_str(), "keyword")?;
+        self.write_type(&subroutine.return_type)?;
+        self.write_token(&subroutine.name, "identifier")?;
+
+        self.write_token("(", "symbol")?;
+        self.write_open_tag("parameterList")?;
+        for (i, parameter) in subroutine.parameters.iter().enumerate() {
+            if i > 0 {
+                self.write_token(",", "symbol")?;
+            }
+            self.write_type(&parameter.type_name)?;
+            self.write_token(&parameter.name, "identifier")?;
+        }
+        self.write_close_tag("parameterList")?;
+        self.write_token(")", "symbol")?;
+
+        self.write_open_tag("subroutineBody")?;
+        self.write_token("{", "symbol")?;
+        for var_dec in &subroutine.var_decs {
+            self.write_open_tag("varDec")?;
+            self.write_token("var", "keyword")?;
+            self.write_type(&var_dec.type_name)?;
+            self.write_names(&var_dec.names)?;
+            self.write_close_tag("varDec")?;
+        }
+        self.write_statements(&subroutine.statements)?;
+        self.write_token("}", "symbol")?;
+        self.write_close_tag("subroutineBody")?;
+
+        self.write_close_tag("subroutineDec")
+    }
+
+    fn write_statements(&mut self, statements: &[Statement]) -> io::Result<()> {
+        self.write_open_tag("statements")?;
+        for statement in statements {
+            self.write_statement(statement)?;
+        }
+        self.write_close_tag("statements")
+    }
+
+    fn write_block(&mut self, statements: &[Statement]) -> io::Result<()> {
+        self.write_token("{", "symbol")?;
+        self.write_statements(statements)?;
+        self.write_token("}", "symbol")
+    }
+
+    fn write_statement(&mut self, statement: &Statement) -> io::Result<()> {
+        match &statement.kind {
+            StatementKind::Let { .. } | StatementKind::Do(_) => {
+                self.write_simple_statement(statement, true)
+            }
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.write_open_tag("ifStatement")?;
+                self.write_token("if", "keyword")?;
+                self.write_token("(", "symbol")?;
+                self.write_expression(condition)?;
+                self.write_token(")", "symbol")?;
+                self.write_block(then_branch)?;
+                if let Some(else_branch) = else_branch {
+                    self.write_token("else", "keyword")?;
+                    self.write_block(else_branch)?;
+                }
+                self.write_close_tag("ifStatement")
+            }
+            StatementKind::While { condition, body } => {
+                self.write_open_tag("whileStatement")?;
+                self.write_token("while", "keyword")?;
+                self.write_token("(", "symbol")?;
+                self.write_expression(condition)?;
+                self.write_token(")", "symbol")?;
+                self.write_block(body)?;
+                self.write_close_tag("whileStatement")
+            }
+            StatementKind::For {
+                init,
+                condition,
+                step,
+                body,
+            } => {
+                self.write_open_tag("forStatement")?;
+                self.write_token("for", "keyword")?;
+                self.write_token("(", "symbol")?;
+                self.write_simple_statement(init, true)?;
+                self.write_expression(condition)?;
+                self.write_token(";", "symbol")?;
+                self.write_simple_statement(step, false)?;
+                self.write_token(")", "symbol")?;
+                self.write_block(body)?;
+                self.write_close_tag("forStatement")
+            }
+            StatementKind::Return(value) => {
+                self.write_open_tag("returnStatement")?;
+                self.write_token("return", "keyword")?;
+                if let Some(value) = value {
+                    self.write_expression(value)?;
+                }
+                self.write_token(";", "symbol")?;
+                self.write_close_tag("returnStatement")
+            }
+            StatementKind::Break => {
+                self.write_open_tag("breakStatement")?;
+                self.write_token("break", "keyword")?;
+                self.write_token(";", "symbol")?;
+                self.write_close_tag("breakStatement")
+            }
+            StatementKind::Continue => {
+                self.write_open_tag("continueStatement")?;
+                self.write_token("continue", "keyword")?;
+                self.write_token(";", "symbol")?;
+                self.write_close_tag("continueStatement")
+            }
+        }
+    }
+
+    /// A `let` or `do` statement; the step of a `for` loop has no `;`.
+    fn write_simple_statement(
+        &mut self,
+        statement: &Statement,
+        terminated: bool,
+    ) -> io::Result<()> {
+        let tag = match &statement.kind {
+            StatementKind::Let { name, index, value } => {
+                self.write_open_tag("letStatement")?;
+                self.write_token("let", "keyword")?;
+                self.write_token(name, "identifier")?;
+                if let Some(index) = index {
+                    self.write_token("[", "symbol")?;
+                    self.write_expression(index)?;
+                    self.write_token("]", "symbol")?;
+                }
+                self.write_token("=", "symbol")?;
+                self.write_expression(value)?;
+                "letStatement"
+            }
+            StatementKind::Do(call) => {
+                self.write_open_tag("doStatement")?;
+                self.write_token("do", "keyword")?;
+                self.write_subroutine_call(call)?;
+                "doStatement"
+            }
+            _ => return self.write_statement(statement),
+        };
+        if terminated {
+            self.write_token(";", "symbol")?;
+        }
+        self.write_close_tag(tag)
+    }
+
+    fn write_expression(&mut self, expression: &Expression) -> io::Result<()> {
+        self.write_open_tag("expression")?;
+        self.write_expression_body(expression)?;
+        self.write_close_tag("expression")
+    }
+
+    /// The flat `term (op term)*` sequence inside an `<expression>` tag.
+    fn write_expression_body(&mut self, expression: &Expression) -> io::Result<()> {
+        match expression {
+            Expression::Term(term) => self.write_term(term),
+            Expression::Binary { op, lhs, rhs, .. } => {
+                self.write_expression_body(lhs)?;
+                self.write_token(op.symbol(), "symbol")?;
+                self.write_expression_body(rhs)
+            }
+        }
+    }
+
+    fn write_term(&mut self, term: &Term) -> io::Result<()> {
+        self.write_open_tag("term")?;
+        match &term.kind {
+            TermKind::IntegerConstant(value) => {
+                self.write_token(&value.to_string(), "integerConstant")?
+            }
+            TermKind::StringConstant(value) => self.write_token(value, "stringConstant")?,
+            TermKind::KeywordConstant(constant) => {
+                self.write_token(constant.as_str(), "keyword")?
+            }
+            TermKind::VarName(name) => self.write_token(name, "identifier")?,
+            TermKind::ArrayAccess { name, index } => {
+                self.write_token(name, "identifier")?;
+                self.write_token("[", "symbol")?;
+                self.write_expression(index)?;
+                self.write_token("]", "symbol")?;
+            }
+            TermKind::Call(call) => self.write_subroutine_call(call)?,
+            TermKind::Paren(expression) => {
+                self.write_token("(", "symbol")?;
+                self.write_expression(expression)?;
+                self.write_token(")", "symbol")?;
+            }
+            TermKind::Unary { op, term } => {
+                self.write_token(op.symbol(), "symbol")?;
+                self.write_term(term)?;
+            }
+        }
+        self.write_close_tag("term")
+    }
+
+    fn write_subroutine_call(&mut self, call: &SubroutineCall) -> io::Result<()> {
+        if let Some(receiver) = &call.receiver {
+            self.write_token(receiver, "identifier")?;
+            self.write_token(".", "symbol")?;
+        }
+        self.write_token(&call.name, "identifier")?;
+        self.write_token("(", "symbol")?;
+        self.write_open_tag("expressionList")?;
+        for (i, arg) in call.args.iter().enumerate() {
+            if i > 0 {
+                self.write_token(",", "symbol")?;
+            }
+            self.write_expression(arg)?;
+        }
+        self.write_close_tag("expressionList")?;
+        self.write_token(")", "symbol")
+    }
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use std::fs;
+
+use common::{run_compiler, scratch_dir};
+use compiler_complete_p11::compile_project;
+
+const COUNTER: &str = "class Counter {
+    field int total;
+
+    constructor Counter new() {
+        let total = 0;
+        return this;
+    }
+
+    method void add(int amount) {
+        let total = total + amount;
+        return;
+    }
+
+    function Counter make() {
+        return Counter.new();
+    }
+}";
+
+/// The error messages of compiling `main` with Counter.
+fn errors(main: &str) -> Vec<String> {
+    match compile_project(&[("Main.jack", main), ("Counter.jack", COUNTER)]) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|(_, d)| d.message).collect(),
+    }
+}
+
+fn main_with(statement: &str) -> String {
+    format!(
+        "class Main {{
+    function void main() {{
+        var Counter counter;
+        var String name;
+        let counter = Counter.new();
+        {statement}
+        return;
+    }}
+}}"
+    )
+}
+
+#[test]
+fn method_calls_on_a_variable_use_the_callees_arity() {
+    let classes = compile_project(&[
+        ("Main.jack", &main_with("do counter.add(2);")),
+        ("Counter.jack", COUNTER),
+    ])
+    .unwrap();
+    // the object is argument 0
+    assert!(
+        classes[0].1.contains("call Counter.add 2\n"),
+        "{}",
+        classes[0].1
+    );
+
+    assert_eq!(
+        errors(&main_with("do counter.add(1, 2);")),
+        ["'Counter.add' expects 1 argument(s), got 2"]
+    );
+    assert_eq!(
+        errors(&main_with("do name.appendChar();")),
+        ["'String.appendChar' expects 1 argument(s), got 0"]
+    );
+}
+
+#[test]
+fn functions_called_through_an_object_are_reported() {
+    assert_eq!(
+        errors(&main_with("let counter = counter.make();")),
+        ["function 'Counter.make' is called on an object; call it as Counter.make()"]
+    );
+    assert_eq!(
+        errors(&main_with("let name = name.new(3);")),
+        ["constructor 'String.new' is called on an object; call it as String.new()"]
+    );
+}
+
+#[test]
+fn os_calls_are_checked_against_the_os_api() {
+    let dir = scratch_dir("os_api");
+    let source = dir.join("Main.jack");
+    fs::write(
+        &source,
+        "class Main {
+    function void main() {
+        do Output.printInt(1);
+        do Sound.beep(440);
+        return;
+    }
+}",
+    )
+    .unwrap();
+    let compile = |api: &str| {
+        let api_path = dir.join("os_api.txt");
+        fs::write(&api_path, api).unwrap();
+        let output = run_compiler(
+            &dir,
+            &[
+                "--os-api".as_ref(),
+                api_path.as_os_str(),
+                source.as_os_str(),
+            ],
+        );
+        (
+            output.status.success(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        )
+    };
+
+    // the description replaces the built-in one; Sound is not in it, so is not checked
+    let (ok, stderr) = compile("function void Output.printInt(int i)\n");
+    assert!(ok, "{stderr}");
+
+    let (ok, stderr) = compile(
+        "// a printer that takes a base
+function void Output.printInt(int i, int base)
+function void Sound.beep(int frequency)
+",
+    );
+    assert!(!ok);
+    assert!(
+        stderr.contains("'Output.printInt' expects 2 argument(s), got 1"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Sound.beep"), "{stderr}");
+
+    let (ok, stderr) = compile("function Output.printInt(int i)\n");
+    assert!(!ok);
+    assert!(
+        stderr.contains("OS API line 1: expected 'kind returnType Class.name(...)'"),
+        "{stderr}"
+    );
+}
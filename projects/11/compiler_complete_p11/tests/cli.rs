@@ -0,0 +1,77 @@
+mod common;
+
+use std::fs;
+
+use common::{run_compiler, scratch_dir};
+
+const MAIN: &str = "class Main { function void main() { return; } }";
+const GAME: &str =
+    "class Game { field int score; constructor Game new() { let score = 0; return this; } }";
+
+#[test]
+fn writes_vm_next_to_each_source_file() {
+    let dir = scratch_dir("next_to_source");
+    let project = dir.join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(project.join("Main.jack"), MAIN).unwrap();
+    fs::write(project.join("Game.jack"), GAME).unwrap();
+
+    let output = run_compiler(&dir, &[project.as_os_str()]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(output.stdout.is_empty());
+    assert!(project.join("Main.vm").is_file());
+    assert!(project.join("Game.vm").is_file());
+    // nothing lands in the working directory by default
+    for stray in ["output", "tokens.xml", "output.xml"] {
+        assert!(!dir.join(stray).exists(), "{stray}");
+    }
+    assert!(!project.join("MainT.xml").exists());
+    assert!(!project.join("Main.xml").exists());
+}
+
+#[test]
+fn output_folder_and_xml_files() {
+    let dir = scratch_dir("output_folder");
+    let source = dir.join("Main.jack");
+    fs::write(&source, MAIN).unwrap();
+    let out = dir.join("build");
+
+    let output = run_compiler(
+        &dir,
+        &[
+            "-o".as_ref(),
+            out.as_os_str(),
+            "--tokens".as_ref(),
+            "--xml".as_ref(),
+            source.as_os_str(),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!dir.join("Main.vm").exists());
+    assert_eq!(
+        fs::read_to_string(out.join("Main.vm")).unwrap(),
+        "function Main.main 0\npush constant 0\nreturn\n"
+    );
+
+    let tokens = fs::read_to_string(out.join("MainT.xml")).unwrap();
+    assert!(tokens.starts_with("<tokens>\n<keyword> class </keyword>\n"));
+    assert!(tokens.ends_with("<symbol> } </symbol>\n</tokens>\n"));
+    let tree = fs::read_to_string(out.join("Main.xml")).unwrap();
+    assert!(tree.starts_with("<class>\n"));
+}
+
+#[test]
+fn unknown_option_is_rejected() {
+    let dir = scratch_dir("unknown_option");
+    let output = run_compiler(&dir, &["--frobnicate".as_ref()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown option --frobnicate"));
+}
@@ -0,0 +1,401 @@
+//! Helpers for running the compiler binary, and a small VM interpreter with just enough
+//! of the Jack OS stubbed in to run compiled programs and capture what they print.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A fresh, empty scratch directory for one test.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jack-test-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the compiler with `args` from inside `work_dir`.
+pub fn run_compiler(work_dir: &Path, args: &[&OsStr]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_compiler_complete_p11"))
+        .args(args)
+        .current_dir(work_dir)
+        .output()
+        .unwrap()
+}
+
+/// Compiles `source` as `Main.jack` and returns the generated VM code, or the compiler's
+/// error output if it failed.
+pub fn compile_main(name: &str, source: &str) -> Result<String, String> {
+    compile_main_with(name, source, &[]).map(|(vm, _)| vm)
+}
+
+/// Like `compile_main`, passing extra command-line `options`; also returns what the
+/// compiler printed to stderr, e.g. warnings.
+pub fn compile_main_with(
+    name: &str,
+    source: &str,
+    options: &[&str],
+) -> Result<(String, String), String> {
+    let dir = scratch_dir(name);
+    let input = dir.join("Main.jack");
+    fs::write(&input, source).unwrap();
+    let mut args: Vec<&OsStr> = options.iter().map(OsStr::new).collect();
+    args.push(input.as_os_str());
+    let output = run_compiler(&dir, &args);
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if !output.status.success() {
+        return Err(stderr);
+    }
+    Ok((fs::read_to_string(dir.join("Main.vm")).unwrap(), stderr))
+}
+
+/// Runs the compiled classes in a `Vm` and returns what they printed.
+pub fn run_vm(classes: &[(String, String)]) -> String {
+    let mut vm = Vm::new(classes);
+    vm.run();
+    vm.output
+}
+
+/// The folder of the course program `projects/11/<program>`.
+pub fn program_dir(program: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(program)
+}
+
+/// Compiles the course program in `projects/11/<program>` into a scratch folder with extra
+/// command-line `options`, and returns each generated `(class name, VM code)`.
+pub fn compile_program(program: &str, options: &[&str]) -> Vec<(String, String)> {
+    let source_dir = program_dir(program);
+    let dir = scratch_dir(&format!("{program}{}", options.concat()));
+    let mut args: Vec<&OsStr> = options.iter().map(OsStr::new).collect();
+    args.extend(["-o".as_ref(), dir.as_os_str(), source_dir.as_os_str()]);
+    let output = run_compiler(&dir, &args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut classes = Vec::new();
+    for entry in fs::read_dir(&source_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "jack") {
+            let class = path.file_stem().unwrap().to_str().unwrap().to_string();
+            let vm = fs::read_to_string(dir.join(format!("{class}.vm"))).unwrap();
+            classes.push((class, vm));
+        }
+    }
+    classes.sort();
+    classes
+}
+
+const SP: usize = 0;
+const LCL: usize = 1;
+const ARG: usize = 2;
+const THIS: usize = 3;
+const THAT: usize = 4;
+const TEMP: usize = 5;
+const STATIC: usize = 16;
+const STACK: usize = 256;
+const HEAP: usize = 2048;
+
+// stops programs that never return
+const MAX_STEPS: usize = 10_000_000;
+
+#[derive(Clone)]
+enum VmCommand {
+    Push(String, usize),
+    Pop(String, usize),
+    Arithmetic(String),
+    Label,
+    Goto(String),
+    IfGoto(String),
+    Function(String, usize),
+    Call(String, usize),
+    Return,
+}
+
+#[derive(Clone)]
+struct Instruction {
+    command: VmCommand,
+    // the function the command belongs to, which scopes its labels
+    function: String,
+    // where the class's statics start
+    static_base: usize,
+}
+
+pub struct Vm {
+    code: Vec<Instruction>,
+    functions: HashMap<String, usize>,
+    labels: HashMap<(String, String), usize>,
+    pub ram: Vec<i16>,
+    pc: usize,
+    heap_top: usize,
+    pub output: String,
+}
+
+impl Vm {
+    /// Loads every `(class name, VM code)`. Calls to functions that are not loaded go to
+    /// the stubbed OS.
+    pub fn new(classes: &[(String, String)]) -> Self {
+        let mut code = Vec::new();
+        let mut functions = HashMap::new();
+        let mut labels = HashMap::new();
+        let mut static_base = STATIC;
+
+        for (_, vm) in classes {
+            let mut function = String::new();
+            let mut statics = 0;
+            for line in vm.lines() {
+                let line = line.split("//").next().unwrap_or("");
+                let words: Vec<&str> = line.split_whitespace().collect();
+                let number = |i: usize| -> usize { words[i].parse().unwrap() };
+                let command = match words[..] {
+                    [] => continue,
+                    ["push", segment, _] => VmCommand::Push(segment.to_string(), number(2)),
+                    ["pop", segment, _] => VmCommand::Pop(segment.to_string(), number(2)),
+                    ["label", label] => {
+                        labels.insert((function.clone(), label.to_string()), code.len());
+                        VmCommand::Label
+                    }
+                    ["goto", label] => VmCommand::Goto(label.to_string()),
+                    ["if-goto", label] => VmCommand::IfGoto(label.to_string()),
+                    ["function", name, _] => {
+                        function = name.to_string();
+                        functions.insert(function.clone(), code.len());
+                        VmCommand::Function(function.clone(), number(2))
+                    }
+                    ["call", name, _] => VmCommand::Call(name.to_string(), number(2)),
+                    ["return"] => VmCommand::Return,
+                    [op] => VmCommand::Arithmetic(op.to_string()),
+                    _ => panic!("bad VM command: {line}"),
+                };
+                if let VmCommand::Push(segment, index) | VmCommand::Pop(segment, index) = &command
+                    && segment == "static"
+                {
+                    statics = statics.max(index + 1);
+                }
+                code.push(Instruction {
+                    command,
+                    function: function.clone(),
+                    static_base,
+                });
+            }
+            static_base += statics;
+        }
+
+        let mut ram = vec![0; 32768];
+        ram[SP] = STACK as i16;
+        Self {
+            code,
+            functions,
+            labels,
+            ram,
+            pc: 0,
+            heap_top: HEAP,
+            output: String::new(),
+        }
+    }
+
+    /// Calls `Sys.init` if it was loaded, otherwise `Main.main`, and runs until it returns
+    /// or calls `Sys.halt`.
+    pub fn run(&mut self) {
+        let entry = if self.functions.contains_key("Sys.init") {
+            "Sys.init"
+        } else {
+            "Main.main"
+        };
+        // returning to the end of the code stops the machine
+        self.pc = self.code.len();
+        self.call(entry, 0);
+
+        let mut steps = 0;
+        while self.pc < self.code.len() {
+            steps += 1;
+            assert!(steps < MAX_STEPS, "program did not finish");
+            self.step();
+        }
+    }
+
+    fn push(&mut self, value: i16) {
+        let sp = self.ram[SP] as usize;
+        self.ram[sp] = value;
+        self.ram[SP] += 1;
+    }
+
+    fn pop(&mut self) -> i16 {
+        self.ram[SP] -= 1;
+        self.ram[self.ram[SP] as usize]
+    }
+
+    fn address(&self, segment: &str, index: usize, static_base: usize) -> usize {
+        let base = |register: usize| self.ram[register] as u16 as usize;
+        match segment {
+            "local" => base(LCL) + index,
+            "argument" => base(ARG) + index,
+            "this" => base(THIS) + index,
+            "that" => base(THAT) + index,
+            "pointer" => THIS + index,
+            "temp" => TEMP + index,
+            "static" => static_base + index,
+            _ => panic!("bad segment {segment}"),
+        }
+    }
+
+    fn step(&mut self) {
+        let instruction = self.code[self.pc].clone();
+        self.pc += 1;
+        match &instruction.command {
+            VmCommand::Push(segment, index) => {
+                let value = if segment == "constant" {
+                    *index as i16
+                } else {
+                    self.ram[self.address(segment, *index, instruction.static_base)]
+                };
+                self.push(value);
+            }
+            VmCommand::Pop(segment, index) => {
+                let address = self.address(segment, *index, instruction.static_base);
+                self.ram[address] = self.pop();
+            }
+            VmCommand::Arithmetic(op) => {
+                let y = self.pop();
+                let value = match op.as_str() {
+                    "neg" => y.wrapping_neg(),
+                    "not" => !y,
+                    _ => {
+                        let x = self.pop();
+                        match op.as_str() {
+                            "add" => x.wrapping_add(y),
+                            "sub" => x.wrapping_sub(y),
+                            "and" => x & y,
+                            "or" => x | y,
+                            "eq" => -i16::from(x == y),
+                            "gt" => -i16::from(x > y),
+                            "lt" => -i16::from(x < y),
+                            _ => panic!("bad arithmetic command {op}"),
+                        }
+                    }
+                };
+                self.push(value);
+            }
+            VmCommand::Label => {}
+            VmCommand::Goto(label) => self.pc = self.label(&instruction.function, label),
+            VmCommand::IfGoto(label) => {
+                let target = self.label(&instruction.function, label);
+                if self.pop() != 0 {
+                    self.pc = target;
+                }
+            }
+            VmCommand::Function(_, n_locals) => {
+                for _ in 0..*n_locals {
+                    self.push(0);
+                }
+            }
+            VmCommand::Call(name, n_args) => {
+                self.call(name, *n_args);
+            }
+            VmCommand::Return => {
+                let frame = self.ram[LCL] as usize;
+                let return_address = self.ram[frame - 5] as u16 as usize;
+                let result = self.pop();
+                let arg = self.ram[ARG] as usize;
+                self.ram[arg] = result;
+                self.ram[SP] = (arg + 1) as i16;
+                for (i, register) in [THAT, THIS, ARG, LCL].into_iter().enumerate() {
+                    self.ram[register] = self.ram[frame - 1 - i];
+                }
+                self.pc = return_address;
+            }
+        }
+    }
+
+    fn label(&self, function: &str, label: &str) -> usize {
+        self.labels[&(function.to_string(), label.to_string())]
+    }
+
+    fn call(&mut self, name: &str, n_args: usize) {
+        let Some(&address) = self.functions.get(name) else {
+            let sp = self.ram[SP] as usize;
+            let args: Vec<i16> = self.ram[sp - n_args..sp].to_vec();
+            self.ram[SP] -= n_args as i16;
+            let result = self.os_call(name, &args);
+            self.push(result);
+            return;
+        };
+
+        let sp = self.ram[SP] as usize;
+        self.push(self.pc as i16);
+        for register in [LCL, ARG, THIS, THAT] {
+            self.push(self.ram[register]);
+        }
+        self.ram[ARG] = (sp - n_args) as i16;
+        self.ram[LCL] = self.ram[SP];
+        self.pc = address;
+    }
+
+    fn alloc(&mut self, size: i16) -> i16 {
+        let address = self.heap_top;
+        self.heap_top += size.max(1) as usize;
+        assert!(self.heap_top < 16384, "heap overflow");
+        address as i16
+    }
+
+    /// The OS functions the course programs use. Strings are laid out as
+    /// `[max length, length, chars...]`.
+    fn os_call(&mut self, name: &str, args: &[i16]) -> i16 {
+        match (name, args) {
+            ("Memory.alloc" | "Array.new", [size]) => self.alloc(*size),
+            ("Memory.deAlloc" | "Array.dispose" | "String.dispose", [_]) => 0,
+            ("Math.multiply", [x, y]) => x.wrapping_mul(*y),
+            ("Math.divide", [x, y]) => x / y,
+            ("Math.abs", [x]) => x.wrapping_abs(),
+            ("Math.min", [x, y]) => *x.min(y),
+            ("Math.max", [x, y]) => *x.max(y),
+            ("String.new", [max_length]) => {
+                let string = self.alloc(max_length + 2);
+                self.ram[string as usize] = *max_length;
+                string
+            }
+            ("String.appendChar", [string, c]) => {
+                let string = *string as usize;
+                let length = self.ram[string + 1];
+                assert!(length < self.ram[string], "string is full");
+                self.ram[string + 2 + length as usize] = *c;
+                self.ram[string + 1] += 1;
+                string as i16
+            }
+            ("String.length", [string]) => self.ram[*string as usize + 1],
+            ("String.charAt", [string, i]) => self.ram[*string as usize + 2 + *i as usize],
+            ("Output.printString", [string]) => {
+                let string = *string as usize;
+                let length = self.ram[string + 1] as usize;
+                for i in 0..length {
+                    self.output.push(self.ram[string + 2 + i] as u8 as char);
+                }
+                0
+            }
+            ("Output.printInt", [n]) => {
+                self.output.push_str(&n.to_string());
+                0
+            }
+            ("Output.printChar", [c]) => {
+                self.output.push(*c as u8 as char);
+                0
+            }
+            ("Output.println", []) => {
+                self.output.push('\n');
+                0
+            }
+            ("Sys.halt", []) => {
+                self.pc = self.code.len();
+                0
+            }
+            _ => panic!("no OS stub for {name} with {} arguments", args.len()),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{compile_main, compile_program, run_vm};
+
+#[test]
+fn complex_arrays_program() {
+    let output = run_vm(&compile_program("ComplexArrays", &[]));
+    let results: Vec<&str> = output.lines().collect();
+    assert_eq!(results.len(), 5, "{output}");
+    for line in results {
+        let (expected, actual) = line
+            .strip_prefix("Test ")
+            .and_then(|l| l.split_once(": expected result: "))
+            .and_then(|(_, l)| l.split_once("; actual result: "))
+            .unwrap_or_else(|| panic!("unexpected output line {line:?}"));
+        assert_eq!(actual, expected, "{line}");
+    }
+}
+
+#[test]
+fn nested_array_assignment_inside_call_arguments() {
+    // the right-hand side calls a function that itself assigns through arrays
+    let source = "
+        class Main {
+            static Array s;
+
+            function void main() {
+                var Array a;
+                let a = Array.new(4);
+                let s = Array.new(4);
+                let a[0] = 1;
+                let a[1] = 2;
+                let a[a[a[0]] - 1] = Main.scramble(a[a[0]]) + a[a[1] - 1];
+                do Output.printInt(a[1]);
+                do Output.printInt(s[3]);
+                return;
+            }
+
+            function int scramble(int n) {
+                let s[n + 1] = 7;
+                let s[s[3] - 4] = s[n + 1] * 2;
+                return s[3];
+            }
+        }
+    ";
+    let vm = compile_main("nested_calls", source).unwrap();
+    // a[1] = scramble(2) + a[1] = 14 + 2; s[3] = 14
+    assert_eq!(run_vm(&[("Main".to_string(), vm)]), "1614");
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{compile_main_with, run_vm};
+
+const PROGRAM: &str = "class Main {
+    static int total;
+    const int ROWS = 256;
+    const int WORDS = ROWS * 32;
+    const int SCREEN = 0x4000;
+    const int LAST = SCREEN + WORDS - 1;
+    const int MIN = -32767 - 1;
+    const char SPACE = ' ';
+    const boolean DEBUG = false;
+    enum Color { RED, GREEN = 5, BLUE, WHITE = BLUE * 2 }
+
+    function void main() {
+        let total = WORDS;
+        do Output.printInt(total);
+        do Output.printChar(SPACE);
+        do Output.printInt(LAST);
+        do Output.printChar(SPACE);
+        do Output.printInt(MIN);
+        do Output.printChar(SPACE);
+        do Output.printInt(RED);
+        do Output.printInt(GREEN);
+        do Output.printInt(BLUE);
+        do Output.printInt(WHITE);
+        if (DEBUG) {
+            do Output.printInt(total);
+        }
+        return;
+    }
+}
+";
+
+#[test]
+fn constants_and_enums_have_their_values() {
+    for options in [&["--extensions"][..], &["--extensions", "--no-optimize"]] {
+        let (vm, _) = compile_main_with("constants", PROGRAM, options).unwrap();
+        let output = run_vm(&[("Main".to_string(), vm)]);
+        assert_eq!(output, "8192 24575 -32768 05612", "{options:?}");
+    }
+}
+
+#[test]
+fn constants_are_folded_and_take_no_memory() {
+    let (vm, _) = compile_main_with("constants_folded", PROGRAM, &["--extensions"]).unwrap();
+    let lines: Vec<&str> = vm.lines().map(str::trim).collect();
+    // the static declared before the constants is still static 0
+    assert_eq!(lines[1..3], ["push constant 8192", "pop static 0"]);
+    assert!(!vm.contains("Math.multiply"), "{vm}");
+    // `if (DEBUG)` is decided at compile time
+    assert!(!vm.contains("if-goto"), "{vm}");
+
+    let source = "class Main {
+        field int x;
+        const int ORIGIN = 0;
+        enum Axis { X, Y }
+        field int y;
+        constructor Main new() { let x = ORIGIN; let y = Y; return this; }
+        function void main() { var Main m; let m = Main.new(); return; }
+    }";
+    let (vm, _) = compile_main_with("constants_fields", source, &["--extensions"]).unwrap();
+    let lines: Vec<&str> = vm.lines().map(str::trim).collect();
+    assert_eq!(
+        lines[1..9],
+        [
+            "push constant 2",
+            "call Memory.alloc 1",
+            "pop pointer 0",
+            "push constant 0",
+            "pop this 0",
+            "push constant 1",
+            "pop this 1",
+            "push pointer 0",
+        ]
+    );
+}
+
+#[test]
+fn constant_errors() {
+    let cases = [
+        (
+            "const int A = Math.max(1, 2);",
+            "let total = A;",
+            "the value of constant 'A' is not known at compile time",
+        ),
+        (
+            "const int A = B; const int B = 1;",
+            "let total = A;",
+            "the value of constant 'A' is not known at compile time",
+        ),
+        (
+            "const int A = 1;",
+            "let A = 2;",
+            "cannot assign to constant 'A'",
+        ),
+        (
+            "const int total = 1;",
+            "return;",
+            "'total' is already defined in this class",
+        ),
+        (
+            "enum E { A, B } const int B = 3;",
+            "return;",
+            "'B' is already defined in this class",
+        ),
+    ];
+    for (i, (declarations, statement, message)) in cases.iter().enumerate() {
+        let source = format!(
+            "class Main {{ static int total; {declarations} function void main() {{ {statement} return; }} }}"
+        );
+        let error = compile_main_with(&format!("constant_error{i}"), &source, &["--extensions"])
+            .unwrap_err();
+        assert!(error.contains(message), "{declarations}: {error}");
+    }
+}
+
+#[test]
+fn constants_need_extensions() {
+    let source = "class Main { const int A = 1; function void main() { return; } }";
+    let error = compile_main_with("constants_off", source, &[]).unwrap_err();
+    assert!(error.contains("unexpected identifier 'const'"), "{error}");
+}
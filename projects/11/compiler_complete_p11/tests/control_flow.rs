@@ -0,0 +1,147 @@
+mod common;
+
+use common::{compile_main_with, run_vm};
+
+/// Compiles `body` as the body of `Main.main`, with `int` variables `i`, `n` and `x`
+/// (`x` starts at 5), and returns the VM code and what the program printed.
+fn compile_and_run(name: &str, body: &str, options: &[&str]) -> (String, String) {
+    let source = format!(
+        "class Main {{
+            function void main() {{
+                var int i, n, x;
+                let x = 5;
+                {body}
+                return;
+            }}
+        }}"
+    );
+    let (vm, _) = compile_main_with(name, &source, options).unwrap();
+    let output = run_vm(&[("Main".to_string(), vm.clone())]);
+    (vm, output)
+}
+
+/// The VM code of the statements under test, without the setup and the final `return`.
+fn statements(vm: &str) -> Vec<&str> {
+    let lines: Vec<&str> = vm.lines().collect();
+    lines[3..lines.len() - 2].to_vec()
+}
+
+const PROGRAMS: [&str; 9] = [
+    // comparisons with and without an else, constants on either side
+    "let i = 0; while (i < 4) { if (i > 1) { do Output.printInt(i); } else { do Output.printInt(-i); } let i = i + 1; }",
+    "let i = 3; while (0 < i) { if (2 > i) { do Output.printInt(i); } let i = i - 1; }",
+    "let i = 10; while (~(i = 0)) { let i = i - 3; if (i < 0) { let i = 0; } do Output.printInt(i); }",
+    "if ((x > 1) & ~(x > 9)) { do Output.printInt(1); } else { do Output.printInt(2); }",
+    "if (x = 0) { do Output.printInt(1); } else { do Output.printInt(2); }",
+    // plain integers as conditions: only -1 counts as true
+    "if (x) { do Output.printInt(1); } else { do Output.printInt(2); }",
+    "if (~x) { do Output.printInt(1); } let n = -1; if (n) { do Output.printInt(3); }",
+    "let i = 3; while (~(i - 3 = -3)) { let i = i - 1; } do Output.printInt(i);",
+    // constant conditions
+    "if (true) { do Output.printInt(1); } if (false) { do Output.printInt(2); } else { do Output.printInt(3); } while (false) { do Output.printInt(4); } while (true) { do Output.printInt(5); return; }",
+];
+
+#[test]
+fn optimized_lowering_behaves_like_the_reference_one() {
+    for (i, program) in PROGRAMS.iter().enumerate() {
+        let (_, expected) = compile_and_run(&format!("reference{i}"), program, &["--no-optimize"]);
+        let (_, actual) = compile_and_run(&format!("optimized{i}"), program, &[]);
+        assert_eq!(actual, expected, "{program}");
+    }
+}
+
+#[test]
+fn if_without_else_needs_no_goto() {
+    let (vm, _) = compile_and_run("if_no_else", "if (x < 3) { let x = 1; }", &[]);
+    assert_eq!(
+        statements(&vm),
+        [
+            "push local 2",
+            "push constant 2",
+            "gt",
+            "if-goto IF_FALSE0",
+            "push constant 1",
+            "pop local 2",
+            "label IF_FALSE0",
+        ]
+    );
+}
+
+#[test]
+fn if_else_jumps_on_true() {
+    let (vm, _) = compile_and_run(
+        "if_else",
+        "if (x < n) { let x = 1; } else { let x = 2; }",
+        &[],
+    );
+    assert_eq!(
+        statements(&vm),
+        [
+            "push local 2",
+            "push local 1",
+            "lt",
+            "if-goto IF_TRUE0",
+            "push constant 2",
+            "pop local 2",
+            "goto IF_END0",
+            "label IF_TRUE0",
+            "push constant 1",
+            "pop local 2",
+            "label IF_END0",
+        ]
+    );
+}
+
+#[test]
+fn while_tests_at_the_bottom() {
+    let (vm, _) = compile_and_run("while_loop", "while (i < n) { let i = i + 1; }", &[]);
+    assert_eq!(
+        statements(&vm),
+        [
+            "goto WHILE_EXP0",
+            "label WHILE_LOOP0",
+            "push local 0",
+            "push constant 1",
+            "add",
+            "pop local 0",
+            "label WHILE_EXP0",
+            "push local 0",
+            "push local 1",
+            "lt",
+            "if-goto WHILE_LOOP0",
+        ]
+    );
+
+    let (vm, _) = compile_and_run("while_not", "while (~(i = n)) { let i = i + 1; }", &[]);
+    assert_eq!(
+        statements(&vm)[..5],
+        [
+            "label WHILE_EXP0",
+            "push local 0",
+            "push local 1",
+            "eq",
+            "if-goto WHILE_END0",
+        ]
+    );
+}
+
+#[test]
+fn labels_are_numbered_per_construct_and_function() {
+    let (vm, _) = compile_and_run(
+        "labels",
+        "while (i < 2) { if (i = 1) { let n = 1; } let i = i + 1; } if (x) { let n = 2; }",
+        &["--no-optimize"],
+    );
+    let labels: Vec<&str> = vm.lines().filter(|l| l.starts_with("label")).collect();
+    assert_eq!(
+        labels,
+        [
+            "label WHILE_EXP0",
+            "label IF_FALSE0",
+            "label IF_END0",
+            "label WHILE_END0",
+            "label IF_FALSE1",
+            "label IF_END1",
+        ]
+    );
+}
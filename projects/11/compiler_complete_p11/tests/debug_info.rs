@@ -0,0 +1,102 @@
+mod common;
+
+use std::ffi::OsStr;
+use std::fs;
+
+use common::{run_compiler, scratch_dir};
+
+const PROGRAM: &str = "class Main {
+    static int total;
+
+    function void main() {
+        var int i;
+        let i = 0;
+        while (i < 3) {
+            do Main.add(i,
+                        2);
+            let i = i + 1;
+        }
+        return;
+    }
+
+    function void add(int x, int y) {
+        let total = total + (x * y);
+        return;
+    }
+}
+";
+
+/// Compiles PROGRAM with `options` and returns `Main.vm` and, if written, `Main.vm.map`.
+fn compile(name: &str, options: &[&str]) -> (String, Option<String>) {
+    let dir = scratch_dir(name);
+    let input = dir.join("Main.jack");
+    fs::write(&input, PROGRAM).unwrap();
+    let mut args: Vec<&OsStr> = options.iter().map(OsStr::new).collect();
+    args.push(input.as_os_str());
+    let output = run_compiler(&dir, &args);
+    assert!(output.status.success(), "{output:?}");
+    (
+        fs::read_to_string(dir.join("Main.vm")).unwrap(),
+        fs::read_to_string(dir.join("Main.vm.map")).ok(),
+    )
+}
+
+#[test]
+fn debug_map_links_commands_to_jack_lines() {
+    let (vm, map) = compile("debug_map", &["--debug"]);
+    let map = map.unwrap();
+    let vm: Vec<&str> = vm.lines().collect();
+
+    let mut functions = Vec::new();
+    let mut lines = Vec::new();
+    for line in map.lines().filter(|line| !line.starts_with("//")) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            ["source", file] => assert_eq!(file, "Main.jack"),
+            ["function", name] => functions.push((name, Vec::new())),
+            [segment, index, type_name, name] => functions
+                .last_mut()
+                .unwrap()
+                .1
+                .push(format!("{segment} {index} {type_name} {name}")),
+            [vm_line, jack_line] => {
+                let vm_line: usize = vm_line.parse().unwrap();
+                lines.push((vm[vm_line - 1], jack_line.parse::<usize>().unwrap()));
+            }
+            _ => panic!("unexpected map line {line}"),
+        }
+    }
+
+    assert_eq!(
+        functions,
+        [
+            ("Main.main", vec!["local 0 int i".to_string()]),
+            (
+                "Main.add",
+                vec![
+                    "argument 0 int x".to_string(),
+                    "argument 1 int y".to_string()
+                ]
+            ),
+        ]
+    );
+    // every command is mapped, once and in order
+    assert_eq!(lines.len(), vm.len());
+    let line_of = |command: &str| lines.iter().find(|(c, _)| *c == command).unwrap().1;
+    assert_eq!(line_of("function Main.main 1"), 4);
+    // a statement spread over two lines maps to the line it starts on
+    assert_eq!(line_of("call Main.add 2"), 8);
+    assert_eq!(line_of("return"), 12);
+    assert_eq!(line_of("pop static 0"), 16);
+    // the jump back to the condition comes after the body but belongs to the `while`
+    let goto = lines.iter().find(|(c, _)| c.starts_with("goto")).unwrap();
+    assert_eq!(goto.1, 7, "{goto:?}");
+}
+
+#[test]
+fn debug_map_leaves_the_vm_code_alone() {
+    let (plain, no_map) = compile("debug_plain", &[]);
+    assert!(no_map.is_none());
+    let (debug, _) = compile("debug_with_map", &["--debug"]);
+    assert_eq!(plain, debug);
+}
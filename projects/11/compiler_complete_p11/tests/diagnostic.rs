@@ -0,0 +1,125 @@
+mod common;
+
+use std::fs;
+
+use common::{run_compiler, scratch_dir};
+use compiler_complete_p11::ast::Span;
+use compiler_complete_p11::compile_class;
+use compiler_complete_p11::diagnostic::Diagnostic;
+
+fn error_at(line: usize, column: usize, message: &str) -> Diagnostic {
+    Diagnostic::error(Span { line, column }, message.to_string())
+}
+
+#[test]
+fn render_marks_the_whole_token() {
+    let source = "class Main {\n    function void main() {\n        let count = = 1;\n";
+    let rendered = error_at(3, 19, "expected an expression")
+        .with_expected(&["integer constant", "identifier", "'('"])
+        .render("Main.jack", source);
+    assert_eq!(
+        rendered,
+        "Main.jack:3:19: error: expected an expression
+  |
+3 |         let count = = 1;
+  |                   ^
+  = expected integer constant, identifier or '('
+"
+    );
+
+    let rendered = error_at(3, 13, "undefined variable 'count'").render("Main.jack", source);
+    assert!(
+        rendered.ends_with("3 |         let count = = 1;\n  |             ^^^^^\n"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn render_keeps_tabs_before_the_caret() {
+    let source = "\tlet\tx = 1;\n";
+    let rendered = error_at(1, 6, "undefined variable 'x'").render("Main.jack", source);
+    assert_eq!(
+        rendered,
+        "Main.jack:1:6: error: undefined variable 'x'
+  |
+1 | \tlet\tx = 1;
+  | \t   \t^
+"
+    );
+}
+
+#[test]
+fn render_widens_the_gutter_for_long_line_numbers() {
+    let source = "\n".repeat(11) + "return x;";
+    let rendered = error_at(12, 8, "undefined variable 'x'")
+        .with_expected(&["';'"])
+        .render("Main.jack", &source);
+    assert_eq!(
+        rendered,
+        "Main.jack:12:8: error: undefined variable 'x'
+   |
+12 | return x;
+   |        ^
+   = expected ';'
+"
+    );
+    // a position past the source has no source line to show
+    let rendered = error_at(40, 1, "unexpected end of file").render("Main.jack", &source);
+    assert_eq!(rendered, "Main.jack:40:1: error: unexpected end of file\n");
+}
+
+#[test]
+fn parser_recovers_to_report_several_errors() {
+    let source = "class Main {
+    function void main() {
+        let x = ;
+        do Output.printInt(1;
+        let y = 2
+        return;
+    }
+
+    function int ok() {
+        return 1;
+    }
+
+    method void broken( {
+        return;
+    }
+}";
+    let errors: Vec<(usize, usize, String)> = compile_class(source)
+        .unwrap_err()
+        .into_iter()
+        .map(|d| (d.span.line, d.span.column, d.message))
+        .collect();
+    // one error per broken statement or declaration, and none for `ok` between them
+    assert_eq!(
+        errors,
+        [
+            (3, 17, "unexpected symbol ';'".to_string()),
+            (4, 29, "unexpected symbol ';'".to_string()),
+            (6, 9, "unexpected keyword 'return'".to_string()),
+            (13, 25, "unexpected symbol '{'".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn failed_compilation_ends_with_a_summary() {
+    let dir = scratch_dir("failure_summary");
+    let source = dir.join("Main.jack");
+    fs::write(
+        &source,
+        "class Main { function void main() { let x = 1; let y = 2; return; } }",
+    )
+    .unwrap();
+    let output = run_compiler(&dir, &[source.as_os_str()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.ends_with("error: compilation failed with 2 error(s)\n"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Custom {"), "{stderr}");
+    assert!(!dir.join("Main.vm").exists());
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::{compile_main_with, run_vm};
+
+/// Compiles `body` as the body of `Main.main` with `--extensions` and any other `options`,
+/// runs it and returns what it printed.
+fn run(name: &str, body: &str, options: &[&str]) -> String {
+    let source = format!(
+        "class Main {{
+            function void main() {{
+                var int i, j, n;
+                {body}
+                return;
+            }}
+        }}"
+    );
+    let options: Vec<&str> = ["--extensions"].iter().chain(options).copied().collect();
+    let (vm, _) = compile_main_with(name, &source, &options).unwrap();
+    run_vm(&[("Main".to_string(), vm)])
+}
+
+/// Runs `body` both optimized and with `--no-optimize`, which must agree.
+fn run_both(name: &str, body: &str) -> String {
+    let optimized = run(name, body, &[]);
+    let reference = run(&format!("{name}_reference"), body, &["--no-optimize"]);
+    assert_eq!(optimized, reference, "{body}");
+    optimized
+}
+
+#[test]
+fn for_loop() {
+    let output = run_both(
+        "for",
+        "for (let i = 0; i < 4; let i = i + 1) { do Output.printInt(i); }",
+    );
+    assert_eq!(output, "0123");
+}
+
+#[test]
+fn for_loop_with_a_call_as_step() {
+    let output = run_both(
+        "for_do",
+        "for (let i = 3; i > 0; do Output.printChar(46)) { let i = i - 1; do Output.printInt(i); }",
+    );
+    assert_eq!(output, "2.1.0.");
+}
+
+#[test]
+fn break_and_continue() {
+    let programs = [
+        // continue still runs the step of a for loop
+        (
+            "for (let i = 0; i < 10; let i = i + 1) { if (i = 2) { continue; } if (i = 5) { break; } do Output.printInt(i); }",
+            "0134",
+        ),
+        (
+            "let i = 0; while (i < 6) { let i = i + 1; if (i = 3) { continue; } do Output.printInt(i); }",
+            "12456",
+        ),
+        // the loops with a bottom test and with a constant condition
+        (
+            "let i = 0; while (true) { let i = i + 1; if (i > 3) { break; } do Output.printInt(i); }",
+            "123",
+        ),
+        (
+            "let i = 0; while ((i < 9) & true) { let i = i + 1; if (i = 4) { break; } do Output.printInt(i); }",
+            "123",
+        ),
+        // break leaves only the innermost loop
+        (
+            "for (let i = 0; i < 3; let i = i + 1) { for (let j = 0; j < 3; let j = j + 1) { if (j > i) { break; } do Output.printInt(j); } do Output.println(); }",
+            "0\n01\n012\n",
+        ),
+    ];
+    for (i, (body, expected)) in programs.iter().enumerate() {
+        assert_eq!(
+            run_both(&format!("loop_control{i}"), body),
+            *expected,
+            "{body}"
+        );
+    }
+}
+
+#[test]
+fn char_and_hex_literals() {
+    let output = run_both(
+        "literals",
+        "do Output.printInt('A'); do Output.printChar(' '); do Output.printInt(0x4000); do Output.printChar('''); do Output.printInt(0X7fff);",
+    );
+    assert_eq!(output, "65 16384'32767");
+}
+
+#[test]
+fn else_if_chain() {
+    let body = "let i = 0; while (i < 4) {
+        if (i = 0) { do Output.printChar('a'); }
+        else if (i = 1) { do Output.printChar('b'); }
+        else if (i = 2) { do Output.printChar('c'); }
+        else { do Output.printChar('d'); }
+        let i = i + 1;
+    }";
+    assert_eq!(run_both("else_if", body), "abcd");
+}
+
+#[test]
+fn break_outside_a_loop_is_an_error() {
+    let source = "class Main {
+        function void main() {
+            if (true) { break; }
+            return;
+        }
+    }";
+    let error = compile_main_with("stray_break", source, &["--extensions"]).unwrap_err();
+    assert!(error.contains("Main.jack:3:25"), "{error}");
+    assert!(error.contains("'break' outside of a loop"), "{error}");
+}
+
+#[test]
+fn extensions_are_off_by_default() {
+    // without the flag `for` is an ordinary name
+    let source = "class Main {
+        function void main() {
+            var int for;
+            let for = 0x10;
+            return;
+        }
+    }";
+    let error = compile_main_with("no_extensions", source, &[]).unwrap_err();
+    assert!(error.contains("invalid integer constant '0x10'"), "{error}");
+    let error = compile_main_with("extensions_keyword", source, &["--extensions"]).unwrap_err();
+    assert!(error.contains("unexpected keyword 'for'"), "{error}");
+}
+
+#[test]
+fn character_constant_errors() {
+    let cases = [
+        ("''", "empty character constant"),
+        ("'ab'", "character constant must hold exactly one character"),
+        ("'\u{e9}'", "is not in the Hack character set"),
+    ];
+    for (i, (literal, message)) in cases.iter().enumerate() {
+        let source = format!(
+            "class Main {{ function void main() {{ do Output.printChar({literal}); return; }} }}"
+        );
+        let error =
+            compile_main_with(&format!("bad_char{i}"), &source, &["--extensions"]).unwrap_err();
+        assert!(error.contains(message), "{literal}: {error}");
+    }
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use common::scratch_dir;
+use compiler_complete_p11::formatter::format_source;
+
+const MESSY: &str = "// Example
+class Main{
+  field int x;   // the x
+    static boolean flag ;
+
+
+
+  /** Says hello.
+        * Twice. */
+  function void main( ) {
+  var int i,j;
+      let i=-1+ ~j*(2-3);let j = Math.max(i ,- 2 );
+  if(i<0){do Output.printString( \"a  b\" ) ;}else{
+  let i=i
+     // why not
+     +1;
+  }
+  while (~(i=0)) {let i=i-1;}
+  return;}
+}
+";
+
+const TIDY: &str = "// Example
+class Main {
+    field int x; // the x
+    static boolean flag;
+
+    /** Says hello.
+     * Twice. */
+    function void main() {
+        var int i, j;
+        let i = -1 + ~j * (2 - 3);
+        let j = Math.max(i, -2);
+        if (i < 0) {
+            do Output.printString(\"a  b\");
+        } else {
+            let i = i
+                // why not
+                + 1;
+        }
+        while (~(i = 0)) {
+            let i = i - 1;
+        }
+        return;
+    }
+}
+";
+
+#[test]
+fn reprints_in_the_canonical_layout() {
+    assert_eq!(format_source(MESSY, false).unwrap(), TIDY);
+    assert_eq!(format_source(TIDY, false).unwrap(), TIDY);
+}
+
+#[test]
+fn formats_extensions() {
+    let source = "class Main { const char A='A'; enum E {X,Y=0x10}
+    function void main() { var int i;
+    for(let i=0;i<3;let i=i+1){ if (i=1) {continue;} else if (i=2) {break;} } return; } }";
+    let expected = "class Main {
+    const char A = 'A';
+    enum E {
+        X, Y = 0x10
+    }
+    function void main() {
+        var int i;
+        for (let i = 0; i < 3; let i = i + 1) {
+            if (i = 1) {
+                continue;
+            } else if (i = 2) {
+                break;
+            }
+        }
+        return;
+    }
+}
+";
+    assert_eq!(format_source(source, true).unwrap(), expected);
+}
+
+#[test]
+fn errors_are_reported_not_formatted() {
+    let error = format_source("class Main { /* never closed", false).unwrap_err();
+    assert!(
+        error.to_string().contains("unterminated block comment"),
+        "{error}"
+    );
+}
+
+fn jack_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            jack_files(&path, files);
+        } else if path.extension().is_some_and(|e| e == "jack") {
+            files.push(path);
+        }
+    }
+}
+
+/// Every course file that tokenizes formats without changing its tokens (which
+/// `format_source` checks), and formatting it again changes nothing.
+#[test]
+fn course_sources_format_stably() {
+    let projects = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let mut files = Vec::new();
+    for project in ["9", "10", "11", "12"] {
+        jack_files(&projects.join(project), &mut files);
+    }
+    let mut formatted_count = 0;
+    for path in files {
+        let source = fs::read_to_string(&path).unwrap();
+        let Ok(formatted) = format_source(&source, false) else {
+            continue;
+        };
+        formatted_count += 1;
+        let again = format_source(&formatted, false).unwrap();
+        assert_eq!(again, formatted, "{} is not stable", path.display());
+    }
+    assert!(
+        formatted_count > 40,
+        "only {formatted_count} files formatted"
+    );
+}
+
+#[test]
+fn check_mode_reports_without_writing() {
+    let dir = scratch_dir("jackfmt");
+    let path = dir.join("Main.jack");
+    fs::write(&path, MESSY).unwrap();
+    let jackfmt = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_jackfmt"))
+            .args(args)
+            .arg(&dir)
+            .output()
+            .unwrap()
+    };
+
+    let output = jackfmt(&["--check"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Main.jack is not formatted"));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "error: 1 file(s) are not formatted\n"
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), MESSY);
+
+    assert!(jackfmt(&[]).status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), TIDY);
+    let output = jackfmt(&["--check"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn unparsable_files_end_with_a_summary() {
+    let dir = scratch_dir("jackfmt_error");
+    fs::write(
+        dir.join("Main.jack"),
+        "class Main {\n    let s = \"no end;\n}\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_jackfmt"))
+        .arg(&dir)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Main.jack:2:13: error: unterminated string constant"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.ends_with("error: 1 file(s) could not be formatted\n"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Custom {"), "{stderr}");
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::fs;
+
+use common::{compile_main, compile_program, program_dir};
+
+/// Trims every line and renames labels to `L0`, `L1`, ... in order of first use within
+/// each function (labels are local to it), so two compilers that only name their labels
+/// differently produce the same text.
+fn normalize(vm: &str) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    vm.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[..] {
+                [command @ ("label" | "goto" | "if-goto"), label] => {
+                    let n = match labels.iter().position(|l| l == label) {
+                        Some(n) => n,
+                        None => {
+                            labels.push(label.to_string());
+                            labels.len() - 1
+                        }
+                    };
+                    format!("{command} L{n}")
+                }
+                ["function", ..] => {
+                    labels.clear();
+                    line.to_string()
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn pong_matches_reference_vm() {
+    let pong = program_dir("Pong");
+    for (class, actual) in compile_program("Pong", &["--no-optimize"]) {
+        let expected = fs::read_to_string(pong.join(format!("{class}.vm"))).unwrap();
+        assert_eq!(normalize(&actual), normalize(&expected), "{class}.vm");
+    }
+}
+
+#[test]
+fn string_constant_has_no_quotes() {
+    let vm = compile_main(
+        "string",
+        "class Main { function void main() { do Output.printString(\"Hi!\"); return; } }",
+    )
+    .unwrap();
+    let expected = [
+        "push constant 3",
+        "call String.new 1",
+        "push constant 72",
+        "call String.appendChar 2",
+        "push constant 105",
+        "call String.appendChar 2",
+        "push constant 33",
+        "call String.appendChar 2",
+        "call Output.printString 1",
+    ];
+    let lines = normalize(&vm);
+    assert!(lines.windows(expected.len()).any(|w| w == expected), "{vm}");
+}
+
+#[test]
+fn empty_string_constant() {
+    let vm = compile_main(
+        "empty_string",
+        "class Main { function void main() { var String s; let s = \"\"; return; } }",
+    )
+    .unwrap();
+    let lines = normalize(&vm);
+    assert_eq!(
+        lines[1..4],
+        ["push constant 0", "call String.new 1", "pop local 0"]
+    );
+}
+
+#[test]
+fn character_outside_hack_set_is_rejected() {
+    let stderr = compile_main(
+        "non_ascii",
+        "class Main {\n  function void main() {\n    do Output.printString(\"caf\u{e9}\");\n    return;\n  }\n}\n",
+    )
+    .unwrap_err();
+    assert!(stderr.contains("Main.jack:3:31"), "{stderr}");
+    assert!(stderr.contains("not in the Hack character set"), "{stderr}");
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{compile_main, run_vm};
+use compiler_complete_p11::compiler::{CompileOptions, compile_project_with};
+use compiler_complete_p11::diagnostic::Severity;
+use compiler_complete_p11::{compile_class, compile_project};
+
+const MAIN: &str = "class Main {
+    function void main() {
+        var Counter counter;
+        let counter = Counter.new();
+        do counter.add(40);
+        do counter.add(2);
+        do Output.printInt(counter.total());
+        return;
+    }
+}
+";
+
+const COUNTER: &str = "class Counter {
+    field int total;
+
+    constructor Counter new() {
+        let total = 0;
+        return this;
+    }
+
+    method void add(int amount) {
+        let total = total + amount;
+        return;
+    }
+
+    method int total() {
+        return total;
+    }
+}
+";
+
+#[test]
+fn compile_class_matches_the_compiler() {
+    let vm = compile_class(COUNTER).unwrap();
+    assert_eq!(vm, compile_main("library_counter", COUNTER).unwrap());
+}
+
+#[test]
+fn compile_class_returns_its_errors() {
+    let errors =
+        compile_class("class Main { function void main() { let x = 1; return; } }").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "undefined variable 'x'");
+    assert_eq!((errors[0].span.line, errors[0].span.column), (1, 37));
+}
+
+#[test]
+fn compile_project_compiles_classes_together() {
+    let classes = compile_project(&[("Main.jack", MAIN), ("Counter.jack", COUNTER)]).unwrap();
+    let names: Vec<&str> = classes.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["Main.jack", "Counter.jack"]);
+    let classes: Vec<(String, String)> = classes
+        .into_iter()
+        .map(|(name, vm)| (name.trim_end_matches(".jack").to_string(), vm))
+        .collect();
+    assert_eq!(run_vm(&classes), "42");
+
+    // a call is checked against the other classes
+    let main = MAIN.replace("counter.total()", "counter.sum()");
+    let errors = compile_project(&[("Main.jack", &main), ("Counter.jack", COUNTER)]).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "Main.jack");
+    assert_eq!(errors[0].1.message, "undefined subroutine 'Counter.sum'");
+}
+
+#[test]
+fn compile_project_with_options() {
+    let options = CompileOptions {
+        debug: true,
+        ..CompileOptions::default()
+    };
+    let unused = COUNTER.replace(
+        "method int total()",
+        "method int unused() { return 0; }\n    method int total()",
+    );
+    let compilation = compile_project_with(
+        &[("src/Main.jack", MAIN), ("src/Counter.jack", &unused)],
+        &options,
+    )
+    .unwrap();
+
+    assert_eq!(compilation.error_count(), 0);
+    let warnings: Vec<(&str, &str)> = compilation
+        .diagnostics
+        .iter()
+        .map(|(file, d)| {
+            assert_eq!(d.severity, Severity::Warning);
+            (file.as_str(), d.message.as_str())
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        [(
+            "src/Counter.jack",
+            "method 'Counter.unused' is never called [uncalled]"
+        )]
+    );
+
+    let counter = &compilation.classes[1];
+    assert_eq!(counter.class.name, "Counter");
+    let debug_map = counter.debug_map.as_deref().unwrap();
+    assert!(debug_map.contains("source Counter.jack\n"), "{debug_map}");
+    assert!(
+        debug_map
+            .contains("function Counter.add\nargument 0 Counter this\nargument 1 int amount\n")
+    );
+}
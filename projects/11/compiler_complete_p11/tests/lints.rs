@@ -0,0 +1,118 @@
+mod common;
+
+use common::compile_main_with;
+
+const PROGRAM: &str = "class Main {
+    field int unusedField;
+    static int counter;
+
+    function void main() {
+        var int a, b, unused, written;
+        let written = 3;
+        let b = a + 1;
+        if (b > 0) {
+            let a = 2;
+        } else {
+            let a = 3;
+        }
+        do Main.log(a, b);
+        return;
+        let b = 0;
+    }
+
+    function void log(int value, int ignored) {
+        let counter = counter + value;
+        return;
+    }
+
+    function int helper() {
+        if (counter > 0) {
+            return 1;
+        } else {
+            return 2;
+        }
+        return 3;
+    }
+}
+";
+
+fn warnings(options: &[&str]) -> Vec<String> {
+    let (_, stderr) = compile_main_with("lints", PROGRAM, options)
+        .map_err(|e| e.to_string())
+        .unwrap();
+    stderr
+        .lines()
+        .filter_map(|line| line.split_once(": warning: "))
+        .map(|(position, message)| {
+            let position = position.rsplit_once("Main.jack:").unwrap().1;
+            format!("{position} {message}")
+        })
+        .collect()
+}
+
+#[test]
+fn reports_each_kind_of_warning() {
+    assert_eq!(
+        warnings(&[]),
+        [
+            "2:15 field 'unusedField' is never used [unused]",
+            "6:23 local variable 'unused' is never used [unused]",
+            "6:31 local variable 'written' is assigned but never used [unused]",
+            "8:17 local variable 'a' is read before it is assigned [uninitialized]",
+            "16:9 unreachable statement [unreachable]",
+            "19:34 parameter 'ignored' is never used [unused]",
+            "24:5 function 'Main.helper' is never called [uncalled]",
+            "30:9 unreachable statement [unreachable]",
+        ]
+    );
+}
+
+#[test]
+fn each_kind_can_be_allowed() {
+    let remaining = warnings(&["--allow", "unused", "--allow", "unreachable"]);
+    assert_eq!(
+        remaining,
+        [
+            "8:17 local variable 'a' is read before it is assigned [uninitialized]",
+            "24:5 function 'Main.helper' is never called [uncalled]",
+        ]
+    );
+    let all = [
+        "--allow",
+        "unused",
+        "--allow",
+        "unreachable",
+        "--allow",
+        "uncalled",
+        "--allow",
+        "uninitialized",
+    ];
+    assert!(warnings(&all).is_empty());
+}
+
+#[test]
+fn unknown_warning_name_is_rejected() {
+    let error =
+        compile_main_with("lints_unknown", PROGRAM, &["--allow", "everything"]).unwrap_err();
+    assert!(error.contains("unknown warning 'everything'"), "{error}");
+}
+
+#[test]
+fn assignment_on_every_path_counts() {
+    let source = "class Main {
+        function void main() {
+            var int x, y;
+            while (y < 3) { let y = y + 1; }
+            if (y > 1) { let x = 1; } else { return; }
+            do Output.printInt(x);
+            return;
+        }
+    }";
+    let (_, stderr) = compile_main_with("lints_paths", source, &[]).unwrap();
+    let warnings: Vec<&str> = stderr.lines().filter(|l| l.contains("warning")).collect();
+    assert_eq!(warnings.len(), 1, "{stderr}");
+    assert!(
+        warnings[0].contains("'y' is read before it is assigned"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,382 @@
+mod common;
+
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use common::scratch_dir;
+use compiler_complete_p11::json::Json;
+use compiler_complete_p11::lsp::{path_to_uri, read_message, uri_to_path};
+
+const SQUARE: &str = "class Square {
+    field int size;
+    static int count;
+
+    constructor Square new(int s) {
+        let size = s;
+        let count = count + 1;
+        return this;
+    }
+
+    method int area() {
+        var int result;
+        let result = size * size;
+        return result;
+    }
+
+    function int total() {
+        return count;
+    }
+}
+";
+
+const MAIN: &str = "class Main {
+    function void main() {
+        var Square square;
+        let square = Square.new(3);
+        do Output.printInt(square.area());
+        do Output.printInt(Square.total());
+        return;
+    }
+}
+";
+
+fn notification(method: &str, params: Json) -> Json {
+    Json::object([
+        ("jsonrpc", Json::string("2.0")),
+        ("method", Json::string(method)),
+        ("params", params),
+    ])
+}
+
+fn request(id: usize, method: &str, params: Json) -> Json {
+    Json::object([
+        ("jsonrpc", Json::string("2.0")),
+        ("id", Json::number(id)),
+        ("method", Json::string(method)),
+        ("params", params),
+    ])
+}
+
+fn did_open(path: &Path, text: &str) -> Json {
+    notification(
+        "textDocument/didOpen",
+        Json::object([(
+            "textDocument",
+            Json::object([
+                ("uri", Json::String(path_to_uri(path))),
+                ("languageId", Json::string("jack")),
+                ("version", Json::number(1)),
+                ("text", Json::string(text)),
+            ]),
+        )]),
+    )
+}
+
+/// A request about the position `offset` characters into the first `needle` in `source`.
+fn at(id: usize, method: &str, path: &Path, source: &str, needle: &str, offset: usize) -> Json {
+    let start = source.find(needle).expect(needle) + offset;
+    let line = source[..start].matches('\n').count();
+    let character = start - source[..start].rfind('\n').map_or(0, |i| i + 1);
+    request(
+        id,
+        method,
+        Json::object([
+            (
+                "textDocument",
+                Json::object([("uri", Json::String(path_to_uri(path)))]),
+            ),
+            (
+                "position",
+                Json::object([
+                    ("line", Json::number(line)),
+                    ("character", Json::number(character)),
+                ]),
+            ),
+        ]),
+    )
+}
+
+/// The framed bytes of `message`.
+fn frame(message: &Json) -> Vec<u8> {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes()
+}
+
+/// Runs the server on `messages`, ending the session properly, and returns what it sent.
+fn session(messages: &[Json]) -> Vec<Json> {
+    raw_session(&messages.iter().flat_map(frame).collect::<Vec<u8>>())
+}
+
+/// Like `session`, with `input` sent as is between `initialize` and `shutdown`.
+fn raw_session(input: &[u8]) -> Vec<Json> {
+    let mut all = frame(&request(0, "initialize", Json::object([])));
+    all.extend_from_slice(input);
+    all.extend(frame(&request(9999, "shutdown", Json::Null)));
+    all.extend(frame(&notification("exit", Json::Null)));
+
+    let mut server = Command::new(env!("CARGO_BIN_EXE_jack_lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    server.stdin.take().unwrap().write_all(&all).unwrap();
+    let output = server.wait_with_output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let mut replies = Vec::new();
+    let mut stdout = Cursor::new(output.stdout);
+    while let Some(message) = read_message(&mut stdout).unwrap() {
+        replies.push(message);
+    }
+    replies
+}
+
+fn result(replies: &[Json], id: usize) -> &Json {
+    replies
+        .iter()
+        .find(|r| r.get("id").as_usize() == Some(id))
+        .unwrap_or_else(|| panic!("no reply to request {id}"))
+        .get("result")
+}
+
+/// The `(uri, [(line, message)])` of every publishDiagnostics notification.
+fn published(replies: &[Json]) -> Vec<(String, Vec<(usize, String)>)> {
+    replies
+        .iter()
+        .filter(|r| r.get("method").as_str() == Some("textDocument/publishDiagnostics"))
+        .map(|r| {
+            let params = r.get("params");
+            let Json::Array(diagnostics) = params.get("diagnostics") else {
+                panic!("{r}");
+            };
+            let diagnostics = diagnostics
+                .iter()
+                .map(|d| {
+                    (
+                        d.get("range").get("start").get("line").as_usize().unwrap(),
+                        d.get("message").as_str().unwrap().to_string(),
+                    )
+                })
+                .collect();
+            (params.get("uri").as_str().unwrap().to_string(), diagnostics)
+        })
+        .collect()
+}
+
+fn project(name: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+    let dir = scratch_dir(name);
+    fs::write(dir.join("Square.jack"), SQUARE).unwrap();
+    fs::write(dir.join("Main.jack"), MAIN).unwrap();
+    (dir.join("Main.jack"), dir.join("Square.jack"))
+}
+
+#[test]
+fn uris_and_paths() {
+    let path = Path::new("/home/me/my project/Main.jack");
+    let uri = path_to_uri(path);
+    assert_eq!(uri, "file:///home/me/my%20project/Main.jack");
+    assert_eq!(uri_to_path(&uri).unwrap(), path);
+}
+
+#[test]
+fn publishes_diagnostics_for_the_folder_on_open_and_save() {
+    let (main, square) = project("lsp_diagnostics");
+    let broken = MAIN.replace("let square = Square.new(3);", "let sqare = Square.new(3);");
+    let save = |text: &str| {
+        notification(
+            "textDocument/didSave",
+            Json::object([
+                (
+                    "textDocument",
+                    Json::object([("uri", Json::String(path_to_uri(&main)))]),
+                ),
+                ("text", Json::string(text)),
+            ]),
+        )
+    };
+    let replies = session(&[did_open(&main, &broken), save(MAIN)]);
+    let published = published(&replies);
+
+    // every file of the folder, on open and again on save
+    assert_eq!(published.len(), 4, "{published:?}");
+    assert_eq!(published[0].0, path_to_uri(&main));
+    assert_eq!(
+        published[0].1,
+        [(3, "undefined variable 'sqare'".to_string())]
+    );
+    assert_eq!(published[1], (path_to_uri(&square), Vec::new()));
+    assert_eq!(published[2], (path_to_uri(&main), Vec::new()));
+}
+
+#[test]
+fn goes_to_definitions() {
+    let (main, square) = project("lsp_definition");
+    let replies = session(&[
+        did_open(&main, MAIN),
+        at(1, "textDocument/definition", &main, MAIN, "square.area", 0),
+        at(2, "textDocument/definition", &main, MAIN, "area()", 0),
+        at(3, "textDocument/definition", &main, MAIN, "Square.new", 2),
+        at(4, "textDocument/definition", &main, MAIN, "printInt", 0),
+    ]);
+    let location = |id| {
+        let location = result(&replies, id);
+        let start = location.get("range").get("start");
+        (
+            location.get("uri").as_str().unwrap().to_string(),
+            start.get("line").as_usize().unwrap(),
+            start.get("character").as_usize().unwrap(),
+        )
+    };
+    // the variable's name in `var Square square;`
+    assert_eq!(location(1), (path_to_uri(&main), 2, 19));
+    assert_eq!(location(2), (path_to_uri(&square), 10, 15));
+    assert_eq!(location(3), (path_to_uri(&square), 0, 6));
+    // the OS has no source to go to
+    assert!(result(&replies, 4).is_null());
+}
+
+#[test]
+fn hovers_show_declarations() {
+    let (main, square) = project("lsp_hover");
+    let replies = session(&[
+        did_open(&main, MAIN),
+        did_open(&square, SQUARE),
+        at(1, "textDocument/hover", &main, MAIN, "square.area", 0),
+        at(2, "textDocument/hover", &main, MAIN, "area()", 1),
+        at(3, "textDocument/hover", &main, MAIN, "printInt", 0),
+        at(4, "textDocument/hover", &main, MAIN, "Output", 0),
+        at(5, "textDocument/hover", &square, SQUARE, "size * size", 0),
+        at(6, "textDocument/hover", &square, SQUARE, "count;", 0),
+    ]);
+    let hover = |id| {
+        result(&replies, id)
+            .get("contents")
+            .get("value")
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(hover(1), "```jack\nvar Square square\n```\n\n`local 0`");
+    assert_eq!(hover(2), "```jack\nmethod int Square.area()\n```");
+    assert_eq!(
+        hover(3),
+        "```jack\nfunction void Output.printInt(int i)\n```"
+    );
+    assert_eq!(hover(4), "```jack\nclass Output\n```");
+    assert_eq!(hover(5), "```jack\nfield int size\n```\n\n`this 0`");
+    assert_eq!(hover(6), "```jack\nstatic int count\n```\n\n`static 0`");
+}
+
+#[test]
+fn completes_class_members() {
+    let (main, _) = project("lsp_completion");
+    let typing = MAIN.replace(
+        "return;",
+        "do square.\n        do Square.\n        do Math.m",
+    );
+    let change = notification(
+        "textDocument/didChange",
+        Json::object([
+            (
+                "textDocument",
+                Json::object([("uri", Json::String(path_to_uri(&main)))]),
+            ),
+            (
+                "contentChanges",
+                Json::Array(vec![Json::object([("text", Json::string(&typing))])]),
+            ),
+        ]),
+    );
+    let replies = session(&[
+        did_open(&main, MAIN),
+        change,
+        at(1, "textDocument/completion", &main, &typing, "square.\n", 7),
+        at(2, "textDocument/completion", &main, &typing, "Square.\n", 7),
+        at(3, "textDocument/completion", &main, &typing, "Math.m", 6),
+        at(4, "textDocument/completion", &main, &typing, "Output", 3),
+    ]);
+    let labels = |id| -> Vec<String> {
+        let Json::Array(items) = result(&replies, id) else {
+            panic!("{:?}", result(&replies, id));
+        };
+        items
+            .iter()
+            .map(|item| item.get("label").as_str().unwrap().to_string())
+            .collect()
+    };
+    // methods on an object, functions and constructors on a class
+    assert_eq!(labels(1), ["area"]);
+    assert_eq!(labels(2), ["new", "total"]);
+    assert!(labels(3).contains(&"max".to_string()), "{:?}", labels(3));
+    assert!(labels(4).is_empty());
+}
+
+#[test]
+fn unknown_requests_are_errors() {
+    let replies = session(&[request(1, "textDocument/rename", Json::object([]))]);
+    let reply = replies
+        .iter()
+        .find(|r| r.get("id").as_usize() == Some(1))
+        .unwrap();
+    assert_eq!(reply.get("error").get("code"), &Json::Number(-32601.0));
+    assert!(result(&replies, 9999).is_null());
+}
+
+#[test]
+fn bad_messages_do_not_stop_the_server() {
+    let (main, _) = project("lsp_bad_messages");
+    let mut input = b"Content-Length: 5\r\n\r\n{oops".to_vec();
+    input.extend_from_slice(b"Content-Length: many\r\nContent-Type: x\r\n\r\n");
+    input.extend_from_slice(b"Content-Length: 99999999999999\r\n\r\n");
+    input.extend(frame(&request(
+        1,
+        "textDocument/hover",
+        Json::object([("position", Json::object([]))]),
+    )));
+    input.extend(frame(&did_open(&main, MAIN)));
+    input.extend(frame(&at(
+        2,
+        "textDocument/hover",
+        &main,
+        MAIN,
+        "printInt",
+        0,
+    )));
+    let replies = raw_session(&input);
+
+    let errors: Vec<(&Json, &Json)> = replies
+        .iter()
+        .filter(|r| !r.get("error").is_null())
+        .map(|r| (r.get("id"), r.get("error").get("code")))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (&Json::Null, &Json::Number(-32700.0)),
+            (&Json::Null, &Json::Number(-32700.0)),
+            (&Json::Null, &Json::Number(-32700.0)),
+            (&Json::number(1), &Json::Number(-32602.0)),
+        ]
+    );
+    // the messages after them are still answered
+    assert_eq!(
+        result(&replies, 2).get("contents").get("value").as_str(),
+        Some("```jack\nfunction void Output.printInt(int i)\n```")
+    );
+    assert!(result(&replies, 9999).is_null());
+}
+
+#[test]
+fn json_round_trips() {
+    let text = r#"{"a":[1,-2.5,true,null],"b":"quote \" tab \t é 😀","c":{}}"#;
+    let value = Json::parse(text).unwrap();
+    assert_eq!(value.get("b").as_str(), Some("quote \" tab \t é 😀"));
+    assert_eq!(Json::parse(&value.to_string()).unwrap(), value);
+    assert_eq!(
+        Json::parse(r#""\ud83d\ude00""#).unwrap(),
+        Json::string("😀")
+    );
+    assert!(Json::parse("[1,").is_err());
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::{compile_main_with, run_vm};
+
+/// Compiles a `main` that prints each of `expressions` with `x = 7`, `y = -3` and
+/// `big = 30000`, and returns the VM code and the printed values.
+fn compile_and_run(name: &str, expressions: &[&str], options: &[&str]) -> (String, Vec<i16>) {
+    let body: String = expressions
+        .iter()
+        .map(|e| format!("do Output.printInt({e}); do Output.println();\n"))
+        .collect();
+    let source = format!(
+        "class Main {{
+            static int calls;
+            function void main() {{
+                var int x, y, big;
+                let x = 7;
+                let y = -3;
+                let big = 30000;
+                {body}
+                do Output.printInt(calls);
+                return;
+            }}
+            function int next() {{
+                let calls = calls + 1;
+                return calls;
+            }}
+        }}"
+    );
+    let (vm, _) = compile_main_with(name, &source, options).unwrap();
+    let output = run_vm(&[("Main".to_string(), vm.clone())]);
+    (vm, output.lines().map(|l| l.parse().unwrap()).collect())
+}
+
+fn multiply_calls(vm: &str) -> usize {
+    vm.matches("call Math.multiply").count()
+}
+
+const EXPRESSIONS: [&str; 16] = [
+    "4 * 8",
+    "(1 + 2) * -(3 - 10) / 2",
+    "~(5 & 3) | (2 < 3)",
+    "32767 + 1",
+    "x * 0",
+    "Main.next() * 0",
+    "x * 1",
+    "x * 2",
+    "3 * y",
+    "x * 13",
+    "y * -6",
+    "big * 1024",
+    "big * 15",
+    "(x + Main.next()) * 5",
+    "x / 1",
+    "y / -1",
+];
+
+#[test]
+fn optimized_code_computes_the_same_values() {
+    let (_, expected) = compile_and_run("unoptimized", &EXPRESSIONS, &["--no-optimize"]);
+    let (vm, values) = compile_and_run("optimized", &EXPRESSIONS, &[]);
+    assert_eq!(values, expected);
+    // Main.next() is still called where its value is thrown away
+    assert_eq!(values.last(), Some(&2));
+    assert_eq!(multiply_calls(&vm), 0, "{vm}");
+    assert!(!vm.contains("call Math.divide"), "{vm}");
+}
+
+#[test]
+fn constant_expressions_are_folded() {
+    let (vm, values) = compile_and_run("folded", &["(1 + 2) * -(3 - 10) / 2", "32767 + 1"], &[]);
+    assert_eq!(values[..2], [10, -32768]);
+    let pushes: Vec<&str> = vm.lines().filter(|l| l.contains("constant")).collect();
+    assert_eq!(
+        pushes[..4],
+        [
+            "push constant 7",
+            "push constant 3",
+            "push constant 30000",
+            "push constant 10"
+        ]
+    );
+    assert!(vm.contains("push constant 32767\nnot\n"), "{vm}");
+}
+
+#[test]
+fn other_factors_and_divisors_still_call_the_os() {
+    let expressions = ["x * 100", "x * y", "big / 4", "x / 0 + 1"];
+    let (vm, _) = compile_main_with(
+        "kept",
+        &format!(
+            "class Main {{ function void main() {{ var int x, y, big; {} return; }} }}",
+            expressions.map(|e| format!("let x = {e};")).concat()
+        ),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(multiply_calls(&vm), 2, "{vm}");
+    assert_eq!(vm.matches("call Math.divide").count(), 2, "{vm}");
+}
+
+#[test]
+fn powers_of_two_still_divide() {
+    // Jack division truncates toward zero, so a shift would get the negative ones wrong
+    let (vm, values) = compile_and_run("divide_by_four", &["x / 4", "-x / 4", "y / 2"], &[]);
+    assert_eq!(values[..3], [1, -1, -1]);
+    assert_eq!(vm.matches("call Math.divide").count(), 3, "{vm}");
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{compile_main_with, run_vm};
+
+/// Compiles a `main` that prints each of `expressions`, and returns the printed values
+/// and the compiler's warnings.
+fn evaluate(name: &str, expressions: &[&str], options: &[&str]) -> (Vec<i16>, String) {
+    let body: String = expressions
+        .iter()
+        .map(|e| format!("do Output.printInt({e}); do Output.println();\n"))
+        .collect();
+    let source = format!(
+        "class Main {{\n function void main() {{\n var int a, b;\n let a = 6;\n let b = 2;\n{body} return;\n }}\n}}\n"
+    );
+    let (vm, stderr) = compile_main_with(name, &source, options).unwrap();
+    let output = run_vm(&[("Main".to_string(), vm)]);
+    (output.lines().map(|l| l.parse().unwrap()).collect(), stderr)
+}
+
+const EXPRESSIONS: [&str; 7] = [
+    "1 + 2 * 3",
+    "a - b * 2 - 1",
+    "a / b + a * b",
+    "1 + a < b * 4",
+    "(a > b) & (b < a) | (a = 1)",
+    "-a + b * ~0",
+    "a * (b + 1)",
+];
+
+#[test]
+fn left_to_right_by_default() {
+    let (values, _) = evaluate("left_to_right", &EXPRESSIONS, &[]);
+    assert_eq!(values, [9, 7, 18, 0, -1, 4, 18]);
+}
+
+#[test]
+fn precedence_option() {
+    let (values, _) = evaluate("precedence", &EXPRESSIONS, &["--precedence"]);
+    assert_eq!(values, [7, 1, 15, -1, -1, -8, 18]);
+}
+
+#[test]
+fn warns_where_the_modes_differ() {
+    let (_, stderr) = evaluate("warning", &["1 + 2 * 3"], &[]);
+    assert!(
+        stderr.contains("Main.jack:6:26: warning: '*' is applied after '+'"),
+        "{stderr}"
+    );
+
+    let (_, stderr) = evaluate("precedence_warning", &["1 + 2 * 3"], &["--precedence"]);
+    assert!(
+        stderr.contains("warning: '*' is applied before '+'"),
+        "{stderr}"
+    );
+
+    // same result either way: equal or falling precedence, or parenthesised
+    let (_, stderr) = evaluate(
+        "no_warning",
+        &["a * b + 1", "a - b - 1", "a * (b + 1)", "a + b < a | false"],
+        &[],
+    );
+    assert!(stderr.is_empty(), "{stderr}");
+}
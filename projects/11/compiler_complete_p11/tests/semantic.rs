@@ -0,0 +1,225 @@
+use compiler_complete_p11::compile_class;
+use compiler_complete_p11::compiler::{CompileOptions, compile_project_with};
+use compiler_complete_p11::diagnostic::Severity;
+
+/// The `(line, message)` of every error `compile_class` finds in `source`.
+fn errors(source: &str) -> Vec<(usize, String)> {
+    match compile_class(source) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors
+            .into_iter()
+            .map(|d| (d.span.line, d.message))
+            .collect(),
+    }
+}
+
+fn error(line: usize, message: &str) -> (usize, String) {
+    (line, message.to_string())
+}
+
+/// The error messages of `source` compiled with `--extensions`.
+fn extension_errors(source: &str) -> Vec<String> {
+    let options = CompileOptions {
+        extensions: true,
+        ..CompileOptions::default()
+    };
+    compile_project_with(&[("Main.jack", source)], &options)
+        .unwrap()
+        .diagnostics
+        .into_iter()
+        .filter(|(_, d)| d.severity == Severity::Error)
+        .map(|(_, d)| d.message)
+        .collect()
+}
+
+#[test]
+fn undefined_subroutines() {
+    let source = "class Main {
+    function void main() {
+        var Main main;
+        var String name;
+        do Main.run();
+        do helper();
+        do Math.squareRoot(4);
+        // a method the variable's class does not have
+        do main.run();
+        do name.size();
+        return;
+    }
+}";
+    assert_eq!(
+        errors(source),
+        [
+            error(5, "undefined subroutine 'Main.run'"),
+            error(6, "undefined subroutine 'Main.helper'"),
+            error(7, "undefined subroutine 'Math.squareRoot'"),
+            error(9, "undefined subroutine 'Main.run'"),
+            error(10, "undefined subroutine 'String.size'"),
+        ]
+    );
+}
+
+#[test]
+fn wrong_number_of_arguments() {
+    let source = "class Main {
+    function int twice(int x) {
+        return x + x;
+    }
+
+    function void main() {
+        do Main.twice();
+        do Main.twice(1, 2);
+        do Output.printInt(Main.twice(3));
+        return;
+    }
+}";
+    assert_eq!(
+        errors(source),
+        [
+            error(7, "'Main.twice' expects 1 argument(s), got 0"),
+            error(8, "'Main.twice' expects 1 argument(s), got 2"),
+        ]
+    );
+}
+
+#[test]
+fn methods_and_functions_called_the_wrong_way() {
+    let source = "class Main {
+    method void run() {
+        return;
+    }
+
+    function void main() {
+        var Main main;
+        do Main.run();
+        do run();
+        do main.run();
+        do main.main();
+        return;
+    }
+}";
+    assert_eq!(
+        errors(source),
+        [
+            error(
+                8,
+                "method 'Main.run' is called as a function; call it on an object"
+            ),
+            error(
+                9,
+                "method 'run' is called on the current object, which a function does not have"
+            ),
+            error(
+                11,
+                "function 'Main.main' is called on an object; call it as Main.main()"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn this_and_fields_inside_functions() {
+    let source = "class Main {
+    field int size;
+
+    method Main self() {
+        let size = 1;
+        return this;
+    }
+
+    function Main make() {
+        let size = 2;
+        return this;
+    }
+}";
+    assert_eq!(
+        errors(source),
+        [
+            error(10, "field 'size' cannot be used inside a function"),
+            error(11, "'this' cannot be used inside a function"),
+        ]
+    );
+}
+
+#[test]
+fn falling_off_the_end() {
+    let source = "class Main {
+    function int sign(int x) {
+        if (x < 0) {
+            return -1;
+        }
+    }
+
+    function int abs(int x) {
+        if (x < 0) {
+            return -x;
+        } else {
+            return x;
+        }
+    }
+
+    function int count() {
+        var int i;
+        while (i < 10) {
+            return i;
+        }
+    }
+}";
+    assert_eq!(
+        errors(source),
+        [
+            error(
+                2,
+                "function 'sign' returns int but can reach the end without a return"
+            ),
+            error(
+                16,
+                "function 'count' returns int but can reach the end without a return"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn endless_loops_do_not_fall_off_the_end() {
+    let source = "class Main {
+    function int first() {
+        while (true) {
+            return 1;
+        }
+    }
+
+    function int second() {
+        while (~false) {
+            if (Main.first() = 1) {
+                return 2;
+            }
+        }
+    }
+}";
+    assert_eq!(errors(source), []);
+
+    // a `break` ends the loop, unless it belongs to a loop inside it
+    let source = "class Main {
+    function int found() {
+        while (true) {
+            if (Main.found() = 0) {
+                break;
+            }
+        }
+    }
+
+    function int nested() {
+        var int i;
+        for (let i = 0; true; let i = i + 1) {
+            while (i < 3) {
+                break;
+            }
+        }
+    }
+}";
+    assert_eq!(
+        extension_errors(source),
+        ["function 'found' returns int but can reach the end without a return"]
+    );
+}
@@ -0,0 +1,105 @@
+use compiler_complete_p11::diagnostic::from_io_error;
+use compiler_complete_p11::tokenizer::{TOKEN_TYPE, jack_tokenizer};
+
+/// The `(value, line, column)` of every token of `source`.
+fn tokens(source: &str) -> Vec<(String, usize, usize)> {
+    jack_tokenizer::new(source, false)
+        .unwrap()
+        .tokens
+        .into_iter()
+        .map(|t| (t.value, t.line, t.column))
+        .collect()
+}
+
+/// The message and `(line, column)` of the error tokenizing `source` gives.
+fn error(source: &str) -> (String, (usize, usize)) {
+    let err = jack_tokenizer::new(source, false).err().unwrap();
+    let diagnostic = from_io_error(&err).unwrap();
+    (
+        diagnostic.message.clone(),
+        (diagnostic.span.line, diagnostic.span.column),
+    )
+}
+
+fn token(value: &str, line: usize, column: usize) -> (String, usize, usize) {
+    (value.to_string(), line, column)
+}
+
+#[test]
+fn block_comments_span_lines() {
+    let source = "/** Doc\n * comment */\nclass /* one\ntwo */ Main {\n}\n";
+    assert_eq!(
+        tokens(source),
+        [
+            token("class", 3, 1),
+            // positions after a comment count the lines inside it
+            token("Main", 4, 8),
+            token("{", 4, 13),
+            token("}", 5, 1),
+        ]
+    );
+}
+
+#[test]
+fn comments_are_kept_on_request() {
+    let source = "/* a\n b */ x // end\n";
+    let kept: Vec<(TOKEN_TYPE, String)> = jack_tokenizer::with_comments(source, false)
+        .unwrap()
+        .tokens
+        .into_iter()
+        .map(|t| (t.kind, t.value))
+        .collect();
+    assert_eq!(
+        kept,
+        [
+            (TOKEN_TYPE::COMMENT, "/* a\n b */".to_string()),
+            (TOKEN_TYPE::IDENTIFIER, "x".to_string()),
+            (TOKEN_TYPE::COMMENT, "// end".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn comment_markers_inside_strings_are_text() {
+    let source = "let s = \"http://a /* b */\"; // real comment\nreturn;";
+    assert_eq!(
+        tokens(source),
+        [
+            token("let", 1, 1),
+            token("s", 1, 5),
+            token("=", 1, 7),
+            token("http://a /* b */", 1, 9),
+            token(";", 1, 27),
+            token("return", 2, 1),
+            token(";", 2, 7),
+        ]
+    );
+}
+
+#[test]
+fn integer_constants_are_at_most_32767() {
+    assert_eq!(tokens("32767"), [token("32767", 1, 1)]);
+    assert_eq!(
+        error("let x =\n  32768;"),
+        (
+            "integer constant 32768 is larger than 32767".to_string(),
+            (2, 3)
+        )
+    );
+}
+
+#[test]
+fn unterminated_literals_are_reported_where_they_start() {
+    assert_eq!(
+        error("let s = \"no end;\nreturn;"),
+        ("unterminated string constant".to_string(), (1, 9))
+    );
+    assert_eq!(
+        error("class Main {\n  /* never\n closed }\n"),
+        ("unterminated block comment".to_string(), (2, 3))
+    );
+    assert_eq!(
+        error("/** doc never closed"),
+        ("unterminated block comment".to_string(), (1, 1))
+    );
+}
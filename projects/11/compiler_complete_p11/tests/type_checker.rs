@@ -0,0 +1,168 @@
+use compiler_complete_p11::ast::Class;
+use compiler_complete_p11::class_index::{ClassIndex, OS_API};
+use compiler_complete_p11::parser::compilation_engine;
+use compiler_complete_p11::tokenizer::jack_tokenizer;
+use compiler_complete_p11::type_checker;
+
+const POINT: &str = "class Point {
+    field int x, y;
+
+    constructor Point new(int ax, int ay) {
+        let x = ax;
+        let y = ay;
+        return this;
+    }
+
+    method int getX() {
+        return x;
+    }
+
+    method Point plus(Point other) {
+        return Point.new(x + other.getX(), y);
+    }
+}";
+
+fn parse(source: &str) -> Class {
+    let tokens = jack_tokenizer::new(source, false).unwrap();
+    compilation_engine::new(tokens.tokens)
+        .compile_class()
+        .unwrap_or_else(|errors| panic!("{}", errors[0].message))
+}
+
+/// The `(line, message)` of every warning the type checker gives `main`, with Point and
+/// the OS in the index.
+fn warnings(main: &str) -> Vec<(usize, String)> {
+    let (main, point) = (parse(main), parse(POINT));
+    let mut index = ClassIndex::new();
+    index.add_api(OS_API).unwrap();
+    index.add_class(&main);
+    index.add_class(&point);
+    type_checker::check(&main, &index)
+        .into_iter()
+        .map(|d| (d.span.line, d.message))
+        .collect()
+}
+
+fn warning(line: usize, message: &str) -> (usize, String) {
+    (line, message.to_string())
+}
+
+#[test]
+fn object_assigned_to_an_int() {
+    let source = "class Main {
+    function void main() {
+        var int n;
+        var Point p;
+        let n = Point.new(1, 2);
+        let p = 3;
+        let p = null;
+        return;
+    }
+}";
+    assert_eq!(
+        warnings(source),
+        [
+            warning(
+                5,
+                "assigning a value of type Point to 'n', which is declared int"
+            ),
+            warning(
+                6,
+                "assigning a value of type int to 'p', which is declared Point"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn method_called_on_a_primitive() {
+    let source = "class Main {
+    function void main() {
+        var Point p;
+        var int n;
+        let p = Point.new(1, 2);
+        do n.getX();
+        do Output.printInt(p.getX());
+        return;
+    }
+}";
+    assert_eq!(
+        warnings(source),
+        [warning(6, "calling 'getX' on 'n', which is declared int")]
+    );
+}
+
+#[test]
+fn wrong_class_as_an_argument() {
+    let source = "class Main {
+    function void main() {
+        var Point p;
+        var String s;
+        let p = Point.new(1, 2);
+        let s = \"x\";
+        let p = p.plus(s);
+        do Output.printString(p);
+        do Output.printString(s);
+        return;
+    }
+}";
+    assert_eq!(
+        warnings(source),
+        [
+            warning(
+                7,
+                "argument 'other' of Point.plus is declared Point, got String"
+            ),
+            warning(
+                8,
+                "argument 's' of Output.printString is declared String, got Point"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn int_arithmetic_on_objects() {
+    let source = "class Main {
+    function void main() {
+        var Point p;
+        var int n;
+        let p = Point.new(1, 2);
+        let n = p + 1;
+        let n = -p;
+        let n = p.getX() * 2;
+        return;
+    }
+}";
+    assert_eq!(
+        warnings(source),
+        [
+            warning(6, "'+' applied to an object of type Point"),
+            warning(7, "'-' applied to an object of type Point"),
+        ]
+    );
+}
+
+#[test]
+fn arrays_ints_and_chars_mix_freely() {
+    let source = "class Main {
+    function void main() {
+        var Array a;
+        var int n;
+        var char c;
+        var Point p;
+        let a = Array.new(3);
+        let n = a;
+        let a = n + 1;
+        let c = n;
+        let n = c + 1;
+        let a[0] = Point.new(1, 2);
+        let p = a[0];
+        let p = a;
+        do Memory.deAlloc(p);
+        do Output.printChar(n);
+        return;
+    }
+}";
+    assert_eq!(warnings(source), []);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+// the comparison is shared with the project 10 analyzer's tests
+#[path = "../../../10/compiler_p10/tests/xml_diff/mod.rs"]
+mod xml_diff;
+
+use std::fs;
+use std::path::Path;
+
+use common::{run_compiler, scratch_dir};
+use xml_diff::structural_diff;
+
+/// Compiles `projects/10/<program>` with token and parse-tree XML, then compares every
+/// reference `Foo.xml` and `FooT.xml` with what the compiler wrote.
+fn check_program(program: &str) {
+    let source_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../10")
+        .join(program);
+    let dir = scratch_dir(&format!("xml-{program}"));
+    let output = run_compiler(
+        &dir,
+        &[
+            "-o".as_ref(),
+            dir.as_os_str(),
+            "--tokens".as_ref(),
+            "--xml".as_ref(),
+            source_dir.as_os_str(),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut failures = Vec::new();
+    let mut compared = 0;
+    for entry in fs::read_dir(&source_dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "xml") {
+            continue;
+        }
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let expected = fs::read_to_string(&path).unwrap();
+        let actual = fs::read_to_string(dir.join(name)).unwrap_or_default();
+        compared += 1;
+        if let Some(diff) = structural_diff(&expected, &actual) {
+            failures.push(format!("{program}/{name} {diff}"));
+        }
+    }
+    assert!(compared > 0, "no reference XML in {}", source_dir.display());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn array_test() {
+    check_program("ArrayTest");
+}
+
+#[test]
+fn square() {
+    check_program("Square");
+}
+
+#[test]
+fn expression_less_square() {
+    check_program("ExpressionLessSquare");
+}
@@ -1,133 +0,0 @@
-@3030
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=A
-@0
-D=D+A
-@R15
-M=D
-@SP
-AM=M-1
-D=M
-@R15
-A=M
-M=D
-@3040
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=A
-@1
-D=D+A
-@R15
-M=D
-@SP
-AM=M-1
-D=M
-@R15
-A=M
-M=D
-@32
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=A
-@2
-D=D+A
-@R15
-M=D
-@SP
-AM=M-1
-D=M
-@R15
-A=M
-M=D
-@46
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THAT
-D=A
-@6
-D=D+A
-@R15
-M=D
-@SP
-AM=M-1
-D=M
-@R15
-A=M
-M=D
-@THIS
-D=A
-@0
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=A
-@1
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@SP
-A=M-1
-D=M
-A=A-1
-D=D+M
-M=D
-D=A+1
-@SP
-M=D
-@SP
-A=M-1
-D=M
-A=A-1
-D=M-D
-M=D
-D=A+1
-@SP
-M=D
-@THAT
-D=A
-@6
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@SP
-A=M-1
-D=M
-A=A-1
-D=D+M
-M=D
-D=A+1
-@SP
-M=D
@@ -1,428 +0,0 @@
-
-@256
-D=A
-@SP
-M=D
-
-
-@BOOT$ret.0
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@LCL
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@ARG
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THAT
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-D=M 
-@0
-D=D-A 
-@5
-D=D-A 
-@ARG
-M=D 
-@SP
-D=M
-@LCL
-M=D
-@Sys.init
-0;JMP
-(BOOT$ret.0)
-
-        (Main.fibonacci)
-@ARG
-D=M
-@0
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@2
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@SP
-AM=M-1
-D=M
-A=A-1
-D=M-D
-@TRUE_lt1
-D;JLT
-@SP
-A=M-1
-M=0
-@END_lt1
-0;JMP
-(TRUE_lt1)
-@SP
-A=M-1
-M=-1
-(END_lt1)
-@SP
-AM=M-1
-D=M
-@Main.fibonacci$IF_TRUE
-D;JNE
-@Main.fibonacci$IF_FALSE
-0;JMP
-(Main.fibonacci$IF_TRUE)
-@ARG
-D=M
-@0
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-
-@LCL
-D=M
-@R13 
-M=D 
-@5
-D=D-A 
-A=D
-D=M
-@R14
-M=D 
-@SP
-AM=M-1
-D=M 
-@ARG
-A=M 
-M=D 
-@ARG
-D=M 
-D=D+1
-@SP
-M=D
-@R13
-AM=M-1
-D=M
-@THAT
-M=D 
-@R13
-AM=M-1
-D=M
-@THIS
-M=D  
-@R13
-AM=M-1
-D=M
-@ARG
-M=D 
-@R13
-AM=M-1
-D=M
-@LCL
-M=D 
-@R14
-A=M
-0;JMP
-
-(Main.fibonacci$IF_FALSE)
-@ARG
-D=M
-@0
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@2
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@SP
-A=M-1
-D=M
-A=A-1
-D=M-D
-M=D
-D=A+1
-@SP
-M=D
-
-@Main.fibonacci$ret.3
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@LCL
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@ARG
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THAT
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-D=M 
-@1
-D=D-A 
-@5
-D=D-A 
-@ARG
-M=D 
-@SP
-D=M
-@LCL
-M=D
-@Main.fibonacci
-0;JMP
-(Main.fibonacci$ret.3)
-
-        @ARG
-D=M
-@0
-A=D+A
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@1
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@SP
-A=M-1
-D=M
-A=A-1
-D=M-D
-M=D
-D=A+1
-@SP
-M=D
-
-@Main.fibonacci$ret.5
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@LCL
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@ARG
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THAT
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-D=M 
-@1
-D=D-A 
-@5
-D=D-A 
-@ARG
-M=D 
-@SP
-D=M
-@LCL
-M=D
-@Main.fibonacci
-0;JMP
-(Main.fibonacci$ret.5)
-
-        @SP
-A=M-1
-D=M
-A=A-1
-D=D+M
-M=D
-D=A+1
-@SP
-M=D
-
-@LCL
-D=M
-@R13 
-M=D 
-@5
-D=D-A 
-A=D
-D=M
-@R14
-M=D 
-@SP
-AM=M-1
-D=M 
-@ARG
-A=M 
-M=D 
-@ARG
-D=M 
-D=D+1
-@SP
-M=D
-@R13
-AM=M-1
-D=M
-@THAT
-M=D 
-@R13
-AM=M-1
-D=M
-@THIS
-M=D  
-@R13
-AM=M-1
-D=M
-@ARG
-M=D 
-@R13
-AM=M-1
-D=M
-@LCL
-M=D 
-@R14
-A=M
-0;JMP
-
-(Sys.init)
-@6
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-
-@Sys.init$ret.7
-D=A
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@LCL
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@ARG
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THIS
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-@THAT
-D=M
-@SP
-A=M
-M=D
-@SP
-M=M+1
-D=M 
-@1
-D=D-A 
-@5
-D=D-A 
-@ARG
-M=D 
-@SP
-D=M
-@LCL
-M=D
-@Main.fibonacci
-0;JMP
-(Sys.init$ret.7)
-
-        (Sys.init$WHILE)
-@Sys.init$WHILE
-0;JMP
@@ -0,0 +1,86 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::parser::{Parser, VMCOMMAND};
+
+/// Which functions each VM function calls, built from `function`/`call` commands.
+#[derive(Default)]
+pub struct CallGraph {
+    pub calls: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CallGraph {
+    pub fn new() -> Self {
+        Self {
+            calls: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_source(&mut self, source: &str) {
+        let mut parser = Parser::new(source);
+        let mut current_function: Option<String> = None;
+
+        while parser.has_more_commands() {
+            parser.advance();
+
+            match parser.command_type() {
+                VMCOMMAND::CFunction => {
+                    if let Some(f_name) = parser.arg_one() {
+                        self.calls.entry(f_name.to_string()).or_default();
+                        current_function = Some(f_name.to_string());
+                    }
+                }
+                VMCOMMAND::CCall => {
+                    if let (Some(caller), Some(callee)) = (&current_function, parser.arg_one()) {
+                        self.calls
+                            .entry(caller.clone())
+                            .or_default()
+                            .insert(callee.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn contains(&self, f_name: &str) -> bool {
+        self.calls.contains_key(f_name)
+    }
+
+    /// Every function transitively callable from `root`, including `root` itself.
+    pub fn reachable_from(&self, root: &str) -> BTreeSet<String> {
+        let mut reachable = BTreeSet::new();
+        let mut pending = vec![root.to_string()];
+
+        while let Some(f_name) = pending.pop() {
+            if !reachable.insert(f_name.clone()) {
+                continue;
+            }
+            if let Some(callees) = self.calls.get(&f_name) {
+                pending.extend(callees.iter().cloned());
+            }
+        }
+
+        reachable
+    }
+
+    /// Graphviz DOT export; unreachable functions are drawn dashed and grey.
+    pub fn to_dot(&self, reachable: Option<&BTreeSet<String>>) -> String {
+        let mut dot = String::from("digraph calls {\n    node [shape=box];\n");
+
+        for f_name in self.calls.keys() {
+            let style = match reachable {
+                Some(r) if !r.contains(f_name) => " [style=dashed, color=grey]",
+                _ => "",
+            };
+            dot.push_str(&format!("    \"{f_name}\"{style};\n"));
+        }
+        for (caller, callees) in &self.calls {
+            for callee in callees {
+                dot.push_str(&format!("    \"{caller}\" -> \"{callee}\";\n"));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -1,110 +1,195 @@
-use std::fmt::format;
-use std::fs::{self, File, OpenOptions};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+/// How the translated program is started, emitted once at the top of `output.asm`.
+pub enum Bootstrap {
+    /// No bootstrap; the test script sets up SP and the segment pointers itself.
+    None,
+    /// SP=256, then `call Sys.init 0`.
+    Standard,
+    /// Custom initial segment pointers, then `call {entry} 0`.
+    Custom {
+        entry: String,
+        pointers: SegmentPointers,
+    },
+}
 
-use std::io::{BufWriter, Write};
+/// Initial values for SP and the segment base registers. `None` leaves the register untouched.
+pub struct SegmentPointers {
+    pub sp: u16,
+    pub lcl: Option<u16>,
+    pub arg: Option<u16>,
+    pub this: Option<u16>,
+    pub that: Option<u16>,
+}
 
-use std::{env, io};
+impl Default for SegmentPointers {
+    fn default() -> Self {
+        Self {
+            sp: 256,
+            lcl: None,
+            arg: None,
+            this: None,
+            that: None,
+        }
+    }
+}
+
+/// The VM command an emitted ASM line was translated from.
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub function: Option<String>,
+}
+
+/// The assembler allocates `@File.i` variables from RAM 16 upward; the stack starts at 256.
+pub const STATIC_BASE: usize = 16;
+pub const STATIC_LIMIT: usize = 256;
 
 pub struct CodeWriter {
-    pub file: Option<BufWriter<File>>,
+    /// The assembly written so far.
+    pub output: String,
     pub current_file: Option<String>,
     pub label_index: usize,
     pub current_function: Option<String>,
+    /// Emit `// File.vm:LINE  command` before each expansion and record the source map.
+    pub annotate: bool,
+    /// Distinct static indices referenced per file, i.e. the RAM each class needs.
+    pub statics: BTreeMap<String, BTreeSet<usize>>,
+    asm_line: usize,
+    current_source: Option<usize>,
+    source_map: Vec<(usize, SourceLocation)>,
 }
 
 impl CodeWriter {
-    pub fn new() -> io::Result<Self> {
-        let file = OpenOptions::new()
-            .truncate(true)
-            .write(true)
-            .create(true)
-            .open("output.asm")
-            .unwrap();
-
+    pub fn new(bootstrap: &Bootstrap, annotate: bool) -> io::Result<Self> {
         let mut writer = Self {
-            file: Some(BufWriter::new(file)),
+            output: String::new(),
             current_file: None,
             label_index: 0,
             current_function: None,
+            annotate,
+            statics: BTreeMap::new(),
+            asm_line: 0,
+            current_source: None,
+            source_map: Vec::new(),
         };
-        writer.write_init()?;
+        writer.write_init(bootstrap)?;
 
         Ok(writer)
     }
 
-    pub fn write_init(&mut self) -> io::Result<()> {
-        let asm_to_write = format!(
-            r#"
-@256
-D=A
-@SP
-M=D
+    pub fn write_init(&mut self, bootstrap: &Bootstrap) -> io::Result<()> {
+        let (entry, pointers) = match bootstrap {
+            Bootstrap::None => return Ok(()),
+            Bootstrap::Standard => ("Sys.init", &SegmentPointers::default()),
+            Bootstrap::Custom { entry, pointers } => (entry.as_str(), pointers),
+        };
 
-"#
-        );
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
+        let mut asm_to_write = String::new();
+        let registers = [
+            ("SP", Some(pointers.sp)),
+            ("LCL", pointers.lcl),
+            ("ARG", pointers.arg),
+            ("THIS", pointers.this),
+            ("THAT", pointers.that),
+        ];
+        for (register, value) in registers {
+            if let Some(value) = value {
+                asm_to_write.push_str(&format!("@{value}\nD=A\n@{register}\nM=D\n"));
+            }
         }
+        asm_to_write.push('\n');
+
+        self.write_asm(&asm_to_write)?;
 
-        self.write_call("Sys.init", 0)?;
+        self.write_call(entry, 0)?;
 
         Ok(())
     }
-    pub fn write_label(&mut self, label: &str) -> io::Result<()> {
-        let f_name = match self.current_function.as_deref() {
-            Some(n) => n,
-            None => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "label is outside a function",
+
+    /// Marks the start of a VM command's expansion; every ASM line written until the next
+    /// call is attributed to `vm_line` of the current file.
+    pub fn set_source(&mut self, vm_line: usize, command: &str) -> io::Result<()> {
+        self.current_source = None;
+        if self.annotate {
+            let f_name = self.current_file.as_deref().unwrap_or("");
+            let comment = format!("// {f_name}.vm:{vm_line}  {command}\n");
+            self.write_asm(&comment)?;
+        }
+        self.current_source = Some(vm_line);
+
+        Ok(())
+    }
+
+    fn write_asm(&mut self, asm: &str) -> io::Result<()> {
+        self.output.push_str(asm);
+
+        let n_lines = asm.matches('\n').count();
+        if self.annotate
+            && let Some(vm_line) = self.current_source
+        {
+            for asm_line in self.asm_line + 1..=self.asm_line + n_lines {
+                self.source_map.push((
+                    asm_line,
+                    SourceLocation {
+                        file: format!("{}.vm", self.current_file.as_deref().unwrap_or("")),
+                        line: vm_line,
+                        function: self.current_function.clone(),
+                    },
                 ));
             }
-        };
+        }
+        self.asm_line += n_lines;
 
-        let asm_to_write = format!("({}${})\n", f_name, label);
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
+        Ok(())
+    }
+
+    /// The ASM line -> VM location table as tab separated columns.
+    pub fn source_map(&self) -> String {
+        let mut map = String::from("# asm_line\tvm_file\tvm_line\tfunction\n");
+        for (asm_line, location) in &self.source_map {
+            map.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                asm_line,
+                location.file,
+                location.line,
+                location.function.as_deref().unwrap_or("-")
+            ));
         }
+        map
+    }
+
+    /// Labels are scoped to the enclosing function, or to the file for code outside any function.
+    fn label_scope(&self) -> io::Result<&str> {
+        self.current_function
+            .as_deref()
+            .or(self.current_file.as_deref())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "label has no scope"))
+    }
+    pub fn write_label(&mut self, label: &str) -> io::Result<()> {
+        let f_name = self.label_scope()?;
+
+        let asm_to_write = format!("({}${})\n", f_name, label);
+        self.write_asm(&asm_to_write)?;
 
         Ok(())
     }
     pub fn write_goto(&mut self, label: &str) -> io::Result<()> {
-        let f_name = self
-            .current_function
-            .as_deref()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "goto outside function"))?;
+        let f_name = self.label_scope()?;
         let asm_to_write = format!("@{f_name}${label}\n0;JMP\n", f_name = f_name, label = label);
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
-        }
+        self.write_asm(&asm_to_write)?;
 
         Ok(())
     }
     pub fn write_if_goto(&mut self, label: &str) -> io::Result<()> {
-        let f_name = self
-            .current_function
-            .as_deref()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "if-goto outside function"))?;
+        let f_name = self.label_scope()?;
         let asm_to_write = format!(
             "@SP\nAM=M-1\nD=M\n@{f_name}${label}\nD;JNE\n",
             f_name = f_name,
             label = label
         );
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
-        }
+        self.write_asm(&asm_to_write)?;
 
         Ok(())
     }
@@ -167,22 +252,15 @@ M=D
 @{callee}
 0;JMP
 ({return_label})
-
-        "#
+"#
         );
 
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
-        }
+        self.write_asm(&asm_to_write)?;
 
         Ok(())
     }
     pub fn write_return(&mut self) -> io::Result<()> {
-        let asm_to_write = format!(
-            r#"
+        let asm_to_write = r#"
 @LCL
 D=M
 @R13 
@@ -228,15 +306,9 @@ M=D
 A=M
 0;JMP
 
-"#
-        );
+"#;
 
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
-        }
+        self.write_asm(asm_to_write)?;
 
         Ok(())
     }
@@ -254,22 +326,12 @@ A=M
             f_name = f_name,
             push_locals = push_locals,
         );
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(asm_to_write.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
-        }
+        self.write_asm(&asm_to_write)?;
 
         Ok(())
     }
 
     pub fn write_arithmetic(&mut self, command: &str) -> io::Result<()> {
-        let writer = self
-            .file
-            .as_mut()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "output file not opened "))?;
-
         let mut machine_code = String::from("");
 
         let mut true_label = String::from("TRUE_");
@@ -311,24 +373,24 @@ A=M
                 )
             }
 
-            _ => panic!("Unknown arithmetic command"),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown arithmetic command: {command}"),
+                ));
+            }
         };
 
         self.label_index += 1;
 
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(machine_code.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
-        }
+        self.write_asm(&machine_code)?;
 
         Ok(())
     }
     pub fn write_push_pop(&mut self, command: &str, segment: &str, index: usize) -> io::Result<()> {
         let segment = segment.to_lowercase();
 
-        let mut machine_code = String::from("");
+        let machine_code: String;
 
         match command {
             "push" => {
@@ -338,9 +400,10 @@ A=M
                             format!("@{index}\nD=A\n@SP\nA=M\nM=D\n@SP\nM=M+1\n", index = index);
                     }
                     "static" => {
-                        let f_name = self.current_file.as_ref().ok_or_else(|| {
-                            io::Error::new(io::ErrorKind::Other, "no current file")
-                        })?;
+                        let f_name = self
+                            .current_file
+                            .as_ref()
+                            .ok_or_else(|| io::Error::other("no current file"))?;
 
                         machine_code = format!(
                             "@{f_name}.{index}\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n",
@@ -386,7 +449,10 @@ A=M
                         )
                     }
                     _ => {
-                        panic!("invalid push seg: {}", segment);
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("invalid push segment: {segment}"),
+                        ));
                     }
                 };
             }
@@ -396,7 +462,7 @@ A=M
                     let f_name = self
                         .current_file
                         .as_ref()
-                        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "no current file"))?;
+                        .ok_or_else(|| io::Error::other("no current file"))?;
 
                     machine_code = format!(
                         "@{f_name}.{index}\nD=A\n@R15\nM=D\n@SP\nAM=M-1\nD=M\n@R15\nA=M\nM=D\n",
@@ -441,35 +507,72 @@ A=M
                     );
                 }
                 _ => {
-                    panic!("invalid pop seg: {}", segment);
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid pop segment: {segment}"),
+                    ));
                 }
             },
             _ => {
-                panic!("invalid command : {}", command);
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid memory command: {command}"),
+                ));
             }
         };
 
-        if let Some(f) = self.file.as_mut() {
-            f.write_all(machine_code.as_bytes())?;
-            f.flush()?;
-        } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "no output file"));
+        if segment == "static"
+            && let Some(f_name) = self.current_file.clone()
+        {
+            self.statics.entry(f_name).or_default().insert(index);
         }
 
+        self.write_asm(&machine_code)?;
+
+        Ok(())
+    }
+
+    pub fn static_count(&self) -> usize {
+        self.statics.values().map(|indices| indices.len()).sum()
+    }
+
+    /// Fails when the statics no longer fit below the stack and would be placed at RAM 256+.
+    pub fn check_static_segment(&self) -> io::Result<()> {
+        let available = STATIC_LIMIT - STATIC_BASE;
+        let used = self.static_count();
+        if used > available {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "static segment overflow: {used} static variables but only {available} fit in RAM {STATIC_BASE}-{}",
+                    STATIC_LIMIT - 1
+                ),
+            ));
+        }
         Ok(())
     }
 
+    pub fn static_report(&self) -> String {
+        let mut report = format!(
+            "static segment usage (RAM {STATIC_BASE}-{}):\n",
+            STATIC_LIMIT - 1
+        );
+        for (f_name, indices) in &self.statics {
+            report.push_str(&format!("  {f_name:<20} {:>4}\n", indices.len()));
+        }
+        report.push_str(&format!(
+            "  {:<20} {:>4} / {}\n",
+            "total",
+            self.static_count(),
+            STATIC_LIMIT - STATIC_BASE
+        ));
+        report
+    }
+
     pub fn generate_return_label(&mut self, f_name: &str) -> String {
         let label_to_return = format!("{f_name}$ret.{}", self.label_index, f_name = f_name);
         self.label_index += 1;
 
         label_to_return
     }
-
-    pub fn close(&mut self) -> io::Result<()> {
-        if let Some(mut w) = self.file.take() {
-            w.flush()?;
-        }
-        Ok(())
-    }
 }
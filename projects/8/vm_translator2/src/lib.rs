@@ -0,0 +1,242 @@
+#![allow(clippy::upper_case_acronyms)]
+//! Hack VM to Hack assembly translator (projects 7 and 8).
+
+use std::collections::BTreeSet;
+use std::io;
+
+pub mod call_graph;
+pub mod code_writer;
+pub mod parser;
+
+use call_graph::CallGraph;
+use code_writer::{Bootstrap, CodeWriter};
+use parser::{Parser, VMCOMMAND};
+
+/// Translates one file. Functions missing from `reachable` are left out of the output.
+pub fn translate_file(
+    buffer: &str,
+    code_writer: &mut CodeWriter,
+    reachable: Option<&BTreeSet<String>>,
+) -> io::Result<()> {
+    let mut parser = Parser::new(buffer);
+    let mut skipping = false;
+
+    while parser.has_more_commands() {
+        parser.advance();
+
+        let command_type = parser.command_type();
+        if command_type == VMCOMMAND::CFunction {
+            skipping = match (reachable, parser.arg_one()) {
+                (Some(r), Some(f_name)) => !r.contains(f_name),
+                _ => false,
+            };
+        }
+        if skipping {
+            continue;
+        }
+
+        if let Some(command) = parser.current.as_deref() {
+            code_writer.set_source(parser.current_line(), command)?;
+        }
+
+        match command_type {
+            VMCOMMAND::CArithmetic => {
+                let command_arg = parser.current.as_ref().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing command arg")
+                })?;
+                code_writer.write_arithmetic(command_arg)?;
+            }
+            VMCOMMAND::CPop | VMCOMMAND::CPush => {
+                let command_arg = parser.current.as_ref().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing command arg")
+                })?;
+                let mut command_iter = command_arg.split_whitespace();
+                let command_name = command_iter.next().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing command name")
+                })?;
+                let segment = command_iter.next().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing segment name")
+                })?;
+                let index_str = command_iter
+                    .next()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing index"))?;
+
+                let index = index_str.parse::<usize>().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "{}.vm:{}: invalid index '{index_str}'",
+                            code_writer.current_file.as_deref().unwrap_or(""),
+                            parser.current_line()
+                        ),
+                    )
+                })?;
+                code_writer.write_push_pop(command_name, segment, index)?;
+            }
+            VMCOMMAND::CLabel => {
+                let label = parser.arg_two().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing label name")
+                })?;
+
+                code_writer.write_label(label)?;
+            }
+            VMCOMMAND::CFunction => {
+                let f_name = parser.arg_one().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing function name")
+                })?;
+
+                let n_locals: i16 = parser
+                    .arg_two()
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "missing numLocals in function")
+                    })?
+                    .parse()
+                    .map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "invalid numLocals value")
+                    })?;
+                code_writer.write_function(f_name, n_locals)?;
+            }
+            VMCOMMAND::CCall => {
+                let n_args: i16 = parser
+                    .arg_two()
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "missing numLocals in function")
+                    })?
+                    .parse()
+                    .map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "invalid numLocals value")
+                    })?;
+
+                let callee = parser.arg_one().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing function callee name")
+                })?;
+
+                code_writer.write_call(callee, n_args)?;
+            }
+            VMCOMMAND::CReturn => {
+                code_writer.write_return()?;
+            }
+            VMCOMMAND::CIf => {
+                let label = parser.arg_two().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing label name")
+                })?;
+                code_writer.write_if_goto(label)?;
+            }
+            VMCOMMAND::CGoto => {
+                let label = parser.arg_two().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "missing label name")
+                })?;
+                code_writer.write_goto(label)?;
+            }
+            VMCOMMAND::Invalid => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "unknown VM command at line {}: {}",
+                        parser.current_line(),
+                        parser.current.as_deref().unwrap_or("")
+                    ),
+                ));
+            }
+            VMCOMMAND::None => {}
+        }
+    }
+    Ok(())
+}
+
+/// The bootstrap used when none is requested: standard when `Sys` is among the inputs.
+pub fn default_bootstrap<'a>(file_stems: impl IntoIterator<Item = &'a str>) -> Bootstrap {
+    if file_stems.into_iter().any(|stem| stem == "Sys") {
+        Bootstrap::Standard
+    } else {
+        Bootstrap::None
+    }
+}
+
+/// The function the bootstrap calls, if any.
+pub fn entry_point(bootstrap: &Bootstrap) -> Option<&str> {
+    match bootstrap {
+        Bootstrap::None => None,
+        Bootstrap::Standard => Some("Sys.init"),
+        Bootstrap::Custom { entry, .. } => Some(entry.as_str()),
+    }
+}
+
+/// Options for `translate_with`.
+#[derive(Default)]
+pub struct TranslateOptions {
+    /// The bootstrap to emit; `None` picks the default, see `default_bootstrap`.
+    pub bootstrap: Option<Bootstrap>,
+    /// Mark each command's code with a `// File.vm:N  command` comment and keep the
+    /// ASM line -> VM line map, see `CodeWriter::source_map`.
+    pub annotate: bool,
+    /// Translate functions the entry point never calls as well.
+    pub keep_unreachable: bool,
+}
+
+/// Everything a translation produced, before the static segment is checked.
+pub struct Translation {
+    /// The output, with its source map and static usage.
+    pub code_writer: CodeWriter,
+    pub call_graph: CallGraph,
+    /// The functions the entry point reaches, or `None` without an entry point to start from.
+    pub reachable: Option<BTreeSet<String>>,
+    pub warnings: Vec<String>,
+}
+
+/// Translates `(file_stem, vm_source)` pairs into one assembly program, with the default
+/// bootstrap and unreachable functions removed.
+pub fn translate(inputs: &[(&str, &str)]) -> io::Result<String> {
+    translate_with(inputs, &TranslateOptions::default())
+}
+
+/// Like `translate`, with the bootstrap and the rest chosen by `options`.
+pub fn translate_with(inputs: &[(&str, &str)], options: &TranslateOptions) -> io::Result<String> {
+    let translation = translate_program(inputs, options)?;
+    translation.code_writer.check_static_segment()?;
+    Ok(translation.code_writer.output)
+}
+
+/// Translates the inputs and returns the whole translation, so a caller can still report on
+/// a program whose statics overflow.
+pub fn translate_program(
+    inputs: &[(&str, &str)],
+    options: &TranslateOptions,
+) -> io::Result<Translation> {
+    let default = default_bootstrap(inputs.iter().map(|(stem, _)| *stem));
+    let bootstrap = options.bootstrap.as_ref().unwrap_or(&default);
+
+    let mut call_graph = CallGraph::new();
+    for (_, source) in inputs {
+        call_graph.add_source(source);
+    }
+
+    // without an entry point every function is a potential root, so nothing can be dropped
+    let mut warnings = Vec::new();
+    let reachable = match entry_point(bootstrap) {
+        Some(root) if call_graph.contains(root) => Some(call_graph.reachable_from(root)),
+        Some(root) => {
+            warnings.push(format!(
+                "entry function {root} is not defined, keeping all functions"
+            ));
+            None
+        }
+        None => None,
+    };
+
+    let mut code_writer = CodeWriter::new(bootstrap, options.annotate)?;
+    let kept = reachable.as_ref().filter(|_| !options.keep_unreachable);
+    for (file_stem, source) in inputs {
+        code_writer.current_file = Some(file_stem.to_string());
+        code_writer.current_function = None;
+
+        translate_file(source, &mut code_writer, kept)?;
+    }
+
+    Ok(Translation {
+        code_writer,
+        call_graph,
+        reachable,
+        warnings,
+    })
+}
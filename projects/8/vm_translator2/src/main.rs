@@ -1,153 +1,175 @@
-#![allow(unused)]
-use std::fmt::format;
-use std::fs::{self, File, OpenOptions};
-use std::i16;
-mod code_writer;
-mod parser;
-use code_writer::CodeWriter;
-use parser::{Parser, VMCOMMAND};
-
-use std::io::{BufWriter, Write};
+use std::fs;
+use vm_translator::code_writer::{Bootstrap, SegmentPointers};
+use vm_translator::{TranslateOptions, translate_program};
+
 use std::path::Path;
 use std::path::PathBuf;
-use std::{env, io};
-
-fn process_file(file_name: &Path, mut code_writer: &mut CodeWriter) -> io::Result<()> {
-    let buffer = fs::read_to_string(file_name)?;
-
-    let mut parser = Parser::new(&buffer);
-
-    while parser.has_more_commands() {
-        parser.advance();
-
-        let command_type = parser.command_type();
-
-        match command_type {
-            VMCOMMAND::CArithmetic => {
-                let command_arg = parser.current.as_ref().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing command arg")
-                })?;
-                println!("command is: {}", command_arg);
-                code_writer.write_arithmetic(&command_arg)?;
-            }
-            VMCOMMAND::CPop | VMCOMMAND::CPush => {
-                let command_arg = parser.current.as_ref().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing command arg")
-                })?;
-                let mut command_iter = command_arg.split_whitespace();
-                let command_name = command_iter.next().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing command name")
-                })?;
-                let segment = command_iter.next().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing segment name")
-                })?;
-                let index_str = command_iter
-                    .next()
-                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing index"))?;
-
-                let index = index_str.parse::<usize>().ok().unwrap();
-
-                println!(
-                    "THE command name is: {}. The segment is: {}. The index is {}.",
-                    command_name, segment, index
-                );
-                code_writer.write_push_pop(command_name, segment, index)?;
-            }
-            VMCOMMAND::CLabel => {
-                let label = parser.arg_two().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing label name")
-                })?;
+use std::{env, io, process};
+
+const USAGE: &str = "Usage: vm_translator2 [--bootstrap none|standard|custom] [--entry FUNCTION] \
+[--sp N] [--lcl N] [--arg N] [--this N] [--that N] [--annotate] [--static-report] \
+[--keep-unreachable] [--call-graph FILE] FILE.vm|DIR";
+
+struct Options {
+    input_path: PathBuf,
+    // what is translated and how, see `TranslateOptions`
+    translate: TranslateOptions,
+    static_report: bool,
+    call_graph_path: Option<PathBuf>,
+}
 
-                code_writer.write_label(label)?;
-            }
-            VMCOMMAND::CFunction => {
-                let f_name = parser.arg_one().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing function name")
-                })?;
+fn parse_args() -> io::Result<Options> {
+    let mut input_name = None;
+    let mut bootstrap_mode = None;
+    let mut entry = None;
+    let mut pointers = SegmentPointers::default();
+    let mut custom_pointers = false;
+    let mut annotate = false;
+    let mut static_report = false;
+    let mut keep_unreachable = false;
+    let mut call_graph_path = None;
 
-                let n_locals: i16 = parser
-                    .arg_two()
-                    .ok_or_else(|| {
-                        io::Error::new(io::ErrorKind::InvalidInput, "missing numLocals in function")
-                    })?
-                    .parse()
-                    .map_err(|_| {
-                        io::Error::new(io::ErrorKind::InvalidInput, "invalid numLocals value")
-                    })?;
-                code_writer.write_function(&f_name, n_locals)?;
-            }
-            VMCOMMAND::CCall => {
-                let n_args: i16 = parser
-                    .arg_two()
-                    .ok_or_else(|| {
-                        io::Error::new(io::ErrorKind::InvalidInput, "missing numLocals in function")
-                    })?
-                    .parse()
-                    .map_err(|_| {
-                        io::Error::new(io::ErrorKind::InvalidInput, "invalid numLocals value")
-                    })?;
-
-                let callee = parser.arg_one().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing function callee name")
-                })?;
+    let mut args = env::args();
+    args.next();
 
-                code_writer.write_call(callee, n_args)?;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bootstrap" => {
+                bootstrap_mode = Some(flag_value(&arg, args.next())?);
             }
-            VMCOMMAND::CReturn => {
-                code_writer.write_return()?;
+            "--annotate" => annotate = true,
+            "--static-report" => static_report = true,
+            "--keep-unreachable" => keep_unreachable = true,
+            "--call-graph" => {
+                call_graph_path = Some(PathBuf::from(flag_value(&arg, args.next())?));
             }
-            VMCOMMAND::CIf => {
-                let label = parser.arg_two().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing label name")
-                })?;
-                code_writer.write_if_goto(label)?;
+            "--entry" => {
+                entry = Some(flag_value(&arg, args.next())?);
             }
-            VMCOMMAND::CGoto => {
-                let label = parser.arg_two().ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "missing label name")
+            "--sp" | "--lcl" | "--arg" | "--this" | "--that" => {
+                let value: u16 = flag_value(&arg, args.next())?.parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid address for {arg}"),
+                    )
                 })?;
-                code_writer.write_goto(label)?;
+                match arg.as_str() {
+                    "--sp" => pointers.sp = value,
+                    "--lcl" => pointers.lcl = Some(value),
+                    "--arg" => pointers.arg = Some(value),
+                    "--this" => pointers.this = Some(value),
+                    _ => pointers.that = Some(value),
+                }
+                custom_pointers = true;
             }
-            VMCOMMAND::None => {}
+            _ => input_name = Some(arg),
         }
     }
-    Ok(())
+
+    let Some(input_name) = input_name else {
+        eprintln!("{USAGE}");
+        process::exit(1);
+    };
+
+    // --entry or any pointer flag implies a custom bootstrap
+    let bootstrap_mode = bootstrap_mode
+        .or_else(|| (entry.is_some() || custom_pointers).then(|| "custom".to_string()));
+
+    let bootstrap = match bootstrap_mode.as_deref() {
+        Some("none") => Some(Bootstrap::None),
+        Some("standard") => Some(Bootstrap::Standard),
+        Some("custom") => Some(Bootstrap::Custom {
+            entry: entry.unwrap_or_else(|| "Sys.init".to_string()),
+            pointers,
+        }),
+        None => None,
+        Some(mode) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown bootstrap mode: {mode} (expected none, standard or custom)"),
+            ));
+        }
+    };
+
+    Ok(Options {
+        input_path: PathBuf::from(input_name),
+        translate: TranslateOptions {
+            bootstrap,
+            annotate,
+            keep_unreachable,
+        },
+        static_report,
+        call_graph_path,
+    })
 }
-fn main() -> io::Result<()> {
-    let mut code_writer = CodeWriter::new()?;
 
-    let mut args = env::args();
-    args.next();
+fn flag_value(flag: &str, value: Option<String>) -> io::Result<String> {
+    value.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("missing value for {flag}"),
+        )
+    })
+}
 
-    let input_name = args.next().expect("Please provide a filename as argument");
-    let input_path = PathBuf::from(input_name);
+fn collect_inputs(input_path: &Path) -> io::Result<Vec<PathBuf>> {
     if input_path.is_file() {
-        let file_stem = input_path
+        return Ok(vec![input_path.to_path_buf()]);
+    }
+
+    let mut inputs = Vec::new();
+    for entry in input_path.read_dir()?.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "vm") {
+            inputs.push(path);
+        }
+    }
+    // read_dir order is platform dependent, keep the output stable
+    inputs.sort();
+
+    Ok(inputs)
+}
+
+fn main() -> io::Result<()> {
+    let options = parse_args()?;
+    let inputs = collect_inputs(&options.input_path)?;
+
+    let mut sources = Vec::new();
+    for path in &inputs {
+        let file_stem = path
             .file_stem()
             .and_then(|s| s.to_str())
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid filename"))?
+            .ok_or_else(|| io::Error::other("invalid filename"))?
             .to_string();
+        sources.push((file_stem, fs::read_to_string(path)?));
+    }
 
-        code_writer.current_file = Some(file_stem);
-        process_file(&input_path, &mut code_writer)?;
-    } else if input_path.is_dir() {
-        for entry in input_path.read_dir().expect("read_dir call failed") {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.extension().unwrap().to_str().unwrap() == "vm" {
-                    let file_stem = path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid filename"))?
-                        .to_string();
-                    code_writer.current_file = Some(file_stem);
-
-                    process_file(&path, &mut code_writer)?;
-                }
-            }
-        }
+    let inputs: Vec<(&str, &str)> = sources
+        .iter()
+        .map(|(stem, source)| (stem.as_str(), source.as_str()))
+        .collect();
+    let translation = translate_program(&inputs, &options.translate)?;
+    for warning in &translation.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    if let Some(path) = &options.call_graph_path {
+        let dot = translation
+            .call_graph
+            .to_dot(translation.reachable.as_ref());
+        fs::write(path, dot)?;
+    }
+
+    let code_writer = translation.code_writer;
+    if options.static_report {
+        print!("{}", code_writer.static_report());
+    }
+    code_writer.check_static_segment()?;
+
+    fs::write("output.asm", &code_writer.output)?;
+    if options.translate.annotate {
+        fs::write("output.map", code_writer.source_map())?;
     }
 
-    code_writer.close()?;
     Ok(())
 }
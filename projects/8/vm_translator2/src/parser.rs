@@ -9,11 +9,15 @@ pub enum VMCOMMAND {
     CFunction,
     CReturn,
     CCall,
+    /// A line that is not a VM command.
+    Invalid,
     None,
 }
 
 pub struct Parser {
     pub lines: Vec<String>,
+    /// 1-based source line of each entry in `lines`.
+    pub line_numbers: Vec<usize>,
     pub pos: usize,
     pub current: Option<String>,
 }
@@ -21,16 +25,19 @@ pub struct Parser {
 impl Parser {
     pub fn new(file: &str) -> Self {
         let mut lines = Vec::new();
+        let mut line_numbers = Vec::new();
 
-        for line in file.lines() {
+        for (i, line) in file.lines().enumerate() {
             let sanitized_line = line.split("//").next().unwrap_or("").trim();
             if !sanitized_line.is_empty() {
                 lines.push(sanitized_line.to_string());
+                line_numbers.push(i + 1);
             }
         }
 
         Self {
             lines,
+            line_numbers,
             pos: 0,
             current: None,
         }
@@ -49,26 +56,32 @@ impl Parser {
         }
     }
 
+    /// Source line of the current command, 0 before the first `advance`.
+    pub fn current_line(&self) -> usize {
+        match self.current {
+            Some(_) => self.line_numbers[self.pos - 1],
+            None => 0,
+        }
+    }
+
     pub fn command_type(&self) -> VMCOMMAND {
-        match &self.current {
-            Some(c) if c.contains("pop") => VMCOMMAND::CPop,
-            Some(c) if c.contains("push") => VMCOMMAND::CPush,
-            Some(c)
-                if ["add", "sub", "lt", "eq", "gt", "and", "or", "not", "neg"]
-                    .iter()
-                    .any(|&x| c.split_whitespace().next().unwrap() == x) =>
-            // come up with something better
-            {
+        let Some(c) = &self.current else {
+            return VMCOMMAND::None;
+        };
+
+        match c.split_whitespace().next().unwrap_or("") {
+            "push" => VMCOMMAND::CPush,
+            "pop" => VMCOMMAND::CPop,
+            "add" | "sub" | "lt" | "eq" | "gt" | "and" | "or" | "not" | "neg" => {
                 VMCOMMAND::CArithmetic
             }
-            Some(c) if c.starts_with("function") => VMCOMMAND::CFunction,
-            Some(c) if c.starts_with("label") => VMCOMMAND::CLabel,
-            Some(c) if c.starts_with("if-goto") => VMCOMMAND::CIf,
-            Some(c) if c.starts_with("goto") => VMCOMMAND::CGoto,
-            Some(c) if c.starts_with("call") => VMCOMMAND::CCall,
-            Some(c) if c.starts_with("return") => VMCOMMAND::CReturn,
-            Some(_) => unreachable!("All cases should be handled"),
-            None => VMCOMMAND::None,
+            "function" => VMCOMMAND::CFunction,
+            "label" => VMCOMMAND::CLabel,
+            "if-goto" => VMCOMMAND::CIf,
+            "goto" => VMCOMMAND::CGoto,
+            "call" => VMCOMMAND::CCall,
+            "return" => VMCOMMAND::CReturn,
+            _ => VMCOMMAND::Invalid,
         }
     }
 
@@ -0,0 +1,112 @@
+use vm_translator::code_writer::{Bootstrap, CodeWriter};
+use vm_translator::{TranslateOptions, translate_file, translate_program, translate_with};
+
+const SOURCE: &str = "// adds two numbers
+function Main.add 0
+
+    push argument 0   // x
+    push argument 1
+    add
+    return
+
+function Main.main 0
+push constant 2
+push constant 3
+call Main.add 2
+label END
+goto END
+";
+
+/// Translates SOURCE as `Main.vm` with annotations on.
+fn annotated() -> CodeWriter {
+    let mut code_writer = CodeWriter::new(&Bootstrap::None, true).unwrap();
+    code_writer.current_file = Some("Main".to_string());
+    translate_file(SOURCE, &mut code_writer, None).unwrap();
+    code_writer
+}
+
+#[test]
+fn comments_name_the_vm_line_and_command() {
+    let code_writer = annotated();
+    let vm: Vec<&str> = SOURCE.lines().collect();
+
+    let mut commented = Vec::new();
+    for line in code_writer.output.lines() {
+        let Some(comment) = line.strip_prefix("// ") else {
+            continue;
+        };
+        let (location, command) = comment.split_once("  ").unwrap();
+        let vm_line: usize = location.strip_prefix("Main.vm:").unwrap().parse().unwrap();
+        // the command as written, without its comment
+        let written = vm[vm_line - 1].split("//").next().unwrap().trim();
+        assert_eq!(command, written, "{line}");
+        commented.push(vm_line);
+    }
+    // every command once, in order; blank and comment lines have none
+    assert_eq!(commented, [2, 4, 5, 6, 7, 9, 10, 11, 12, 13, 14]);
+}
+
+#[test]
+fn map_lines_point_at_the_command_they_came_from() {
+    let code_writer = annotated();
+    let asm: Vec<&str> = code_writer.output.lines().collect();
+    let map = code_writer.source_map();
+    let mut rows = map.lines();
+    assert_eq!(rows.next(), Some("# asm_line\tvm_file\tvm_line\tfunction"));
+
+    let mut mapped = 0;
+    for row in rows {
+        let columns: Vec<&str> = row.split('\t').collect();
+        let [asm_line, file, vm_line, function] = columns[..] else {
+            panic!("unexpected map row {row}");
+        };
+        let asm_line: usize = asm_line.parse().unwrap();
+        assert_eq!(file, "Main.vm");
+
+        // the nearest comment above an ASM line names the command it belongs to
+        assert!(!asm[asm_line - 1].starts_with("//"), "{row}");
+        let comment = asm[..asm_line - 1]
+            .iter()
+            .rev()
+            .find(|line| line.starts_with("//"))
+            .unwrap();
+        assert!(
+            comment.starts_with(&format!("// Main.vm:{vm_line}  ")),
+            "{row} under {comment}"
+        );
+
+        let expected = if vm_line.parse::<usize>().unwrap() < 9 {
+            "Main.add"
+        } else {
+            "Main.main"
+        };
+        assert_eq!(function, expected, "{row}");
+        mapped += 1;
+    }
+    // every line but the comments is mapped
+    let comments = asm.iter().filter(|line| line.starts_with("//")).count();
+    assert_eq!(mapped, asm.len() - comments);
+}
+
+#[test]
+fn library_annotates_on_request() {
+    let options = TranslateOptions {
+        bootstrap: Some(Bootstrap::None),
+        annotate: true,
+        ..TranslateOptions::default()
+    };
+    let translation = translate_program(&[("Main", SOURCE)], &options).unwrap();
+    let expected = annotated();
+    assert_eq!(translation.code_writer.output, expected.output);
+    assert_eq!(translation.code_writer.source_map(), expected.source_map());
+    // and not by default
+    let plain = translate_with(
+        &[("Main", SOURCE)],
+        &TranslateOptions {
+            bootstrap: Some(Bootstrap::None),
+            ..TranslateOptions::default()
+        },
+    )
+    .unwrap();
+    assert!(!plain.contains("// Main.vm"), "{plain}");
+}
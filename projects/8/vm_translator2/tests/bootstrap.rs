@@ -0,0 +1,94 @@
+mod common;
+
+use std::process::Command;
+
+use common::Cpu;
+use vm_translator::code_writer::{Bootstrap, SegmentPointers};
+use vm_translator::{TranslateOptions, translate_with};
+
+const SYS: &str = "function Sys.init 0\nlabel LOOP\ngoto LOOP\n";
+const MAIN: &str = "function Main.main 0\nlabel LOOP\ngoto LOOP\n";
+
+fn translate(inputs: &[(&str, &str)], bootstrap: Option<Bootstrap>) -> String {
+    translate_with(
+        inputs,
+        &TranslateOptions {
+            bootstrap,
+            ..TranslateOptions::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn no_bootstrap_sets_nothing_up() {
+    let asm = translate(&[("Sys", SYS)], Some(Bootstrap::None));
+    assert!(!asm.contains("@SP\nM=D"), "{asm}");
+    assert!(!asm.contains("@Sys.init\n0;JMP"), "{asm}");
+}
+
+#[test]
+fn standard_bootstrap_sets_sp_and_calls_sys_init() {
+    let asm = translate(&[("Sys", SYS)], Some(Bootstrap::Standard));
+    assert!(asm.starts_with("@256\nD=A\n@SP\nM=D\n\n"), "{asm}");
+    assert!(asm.contains("@Sys.init\n0;JMP"), "{asm}");
+
+    // the default for a program with a Sys.vm
+    assert_eq!(translate(&[("Sys", SYS)], None), asm);
+}
+
+#[test]
+fn custom_bootstrap_sets_every_pointer_and_calls_the_entry() {
+    let pointers = SegmentPointers {
+        sp: 300,
+        lcl: Some(400),
+        arg: Some(500),
+        this: Some(3000),
+        that: Some(4000),
+    };
+    let bootstrap = Bootstrap::Custom {
+        entry: "Main.main".to_string(),
+        pointers,
+    };
+    let asm = translate(&[("Main", MAIN)], Some(bootstrap));
+    assert!(
+        asm.starts_with(
+            "@300\nD=A\n@SP\nM=D\n@400\nD=A\n@LCL\nM=D\n@500\nD=A\n@ARG\nM=D\n\
+             @3000\nD=A\n@THIS\nM=D\n@4000\nD=A\n@THAT\nM=D\n\n"
+        ),
+        "{asm}"
+    );
+    assert!(asm.contains("@Main.main\n0;JMP"), "{asm}");
+
+    let mut cpu = Cpu::new(&asm);
+    for _ in 0..5 * 4 {
+        cpu.tick();
+    }
+    assert_eq!(cpu.ram[0..5], [300, 400, 500, 3000, 4000]);
+}
+
+#[test]
+fn custom_bootstrap_leaves_unset_pointers_alone() {
+    let bootstrap = Bootstrap::Custom {
+        entry: "Main.main".to_string(),
+        pointers: SegmentPointers {
+            lcl: Some(400),
+            ..SegmentPointers::default()
+        },
+    };
+    let asm = translate(&[("Main", MAIN)], Some(bootstrap));
+    assert!(
+        asm.starts_with("@256\nD=A\n@SP\nM=D\n@400\nD=A\n@LCL\nM=D\n\n"),
+        "{asm}"
+    );
+}
+
+#[test]
+fn missing_input_prints_usage() {
+    let output = Command::new(env!("CARGO_BIN_EXE_vm_translator2"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Usage: vm_translator2"), "{stderr}");
+}
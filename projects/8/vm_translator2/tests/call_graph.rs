@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use vm_translator::call_graph::CallGraph;
+use vm_translator::code_writer::{Bootstrap, SegmentPointers};
+use vm_translator::parser::{Parser, VMCOMMAND};
+use vm_translator::{TranslateOptions, translate, translate_program};
+
+const SYS: &str = "function Sys.init 0
+push constant 1
+call Stack.push 1
+call Stack.pop 0
+label END
+goto END
+";
+
+// `Stack.popAll` is never called
+const STACK: &str = "function Stack.push 0
+push argument 0
+pop static 0
+push constant 0
+return
+function Stack.pop 0
+push static 0
+return
+function Stack.popAll 0
+call Stack.pop 0
+return
+";
+
+/// An empty directory for one test, under cargo's scratch space.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the translator on SYS and STACK in a directory of their own and returns
+/// `output.asm`.
+fn run_translator(name: &str, args: &[&str]) -> (PathBuf, String) {
+    let dir = scratch_dir(name);
+    let program = dir.join("program");
+    fs::create_dir(&program).unwrap();
+    fs::write(program.join("Sys.vm"), SYS).unwrap();
+    fs::write(program.join("Stack.vm"), STACK).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vm_translator2"))
+        .args(args)
+        .arg(&program)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let asm = fs::read_to_string(dir.join("output.asm")).unwrap();
+    (dir, asm)
+}
+
+#[test]
+fn commands_are_classified_by_their_first_word() {
+    let mut parser = Parser::new("call Stack.push 2\nfunction Stack.pop 0\npop local 0\n");
+    let mut types = Vec::new();
+    while parser.has_more_commands() {
+        parser.advance();
+        types.push(parser.command_type());
+    }
+    assert_eq!(
+        types,
+        [VMCOMMAND::CCall, VMCOMMAND::CFunction, VMCOMMAND::CPop]
+    );
+}
+
+#[test]
+fn reachable_functions_follow_calls() {
+    let mut call_graph = CallGraph::new();
+    call_graph.add_source(SYS);
+    call_graph.add_source(STACK);
+
+    let reachable: Vec<String> = call_graph.reachable_from("Sys.init").into_iter().collect();
+    assert_eq!(reachable, ["Stack.pop", "Stack.push", "Sys.init"]);
+    assert!(call_graph.contains("Stack.popAll"));
+}
+
+#[test]
+fn uncalled_functions_are_dropped_unless_kept() {
+    let (_, asm) = run_translator("drop_unreachable", &[]);
+    assert!(asm.contains("(Stack.pop)"), "{asm}");
+    assert!(!asm.contains("(Stack.popAll)"), "{asm}");
+
+    let (_, asm) = run_translator("keep_unreachable", &["--keep-unreachable"]);
+    assert!(asm.contains("(Stack.popAll)"), "{asm}");
+}
+
+#[test]
+fn call_graph_is_written_as_dot() {
+    let (dir, _) = run_translator("call_graph_dot", &["--call-graph", "calls.dot"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("calls.dot")).unwrap(),
+        r#"digraph calls {
+    node [shape=box];
+    "Stack.pop";
+    "Stack.popAll" [style=dashed, color=grey];
+    "Stack.push";
+    "Sys.init";
+    "Stack.popAll" -> "Stack.pop";
+    "Sys.init" -> "Stack.pop";
+    "Sys.init" -> "Stack.push";
+}
+"#
+    );
+}
+
+#[test]
+fn library_drops_uncalled_functions_unless_kept() {
+    let inputs = [("Sys", SYS), ("Stack", STACK)];
+    let asm = translate(&inputs).unwrap();
+    assert!(!asm.contains("(Stack.popAll)"), "{asm}");
+
+    let options = TranslateOptions {
+        keep_unreachable: true,
+        ..TranslateOptions::default()
+    };
+    let translation = translate_program(&inputs, &options).unwrap();
+    assert!(
+        translation.code_writer.output.contains("(Stack.popAll)"),
+        "{}",
+        translation.code_writer.output
+    );
+    // still drawn as unreachable
+    let dot = translation
+        .call_graph
+        .to_dot(translation.reachable.as_ref());
+    assert!(
+        dot.contains("\"Stack.popAll\" [style=dashed, color=grey];"),
+        "{dot}"
+    );
+}
+
+#[test]
+fn missing_entry_function_keeps_everything() {
+    let options = TranslateOptions {
+        bootstrap: Some(Bootstrap::Custom {
+            entry: "Main.main".to_string(),
+            pointers: SegmentPointers::default(),
+        }),
+        ..TranslateOptions::default()
+    };
+    let translation = translate_program(&[("Stack", STACK)], &options).unwrap();
+    assert_eq!(
+        translation.warnings,
+        ["entry function Main.main is not defined, keeping all functions"]
+    );
+    assert!(translation.reachable.is_none());
+    assert!(translation.code_writer.output.contains("(Stack.popAll)"));
+}
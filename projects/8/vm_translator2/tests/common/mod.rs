@@ -0,0 +1,234 @@
+//! A small Hack CPU emulator and test-script reader for running the course's `.tst` files.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+enum Instruction {
+    A(u16),
+    C {
+        dest: String,
+        comp: String,
+        jump: String,
+    },
+}
+
+pub struct Cpu {
+    rom: Vec<Instruction>,
+    pub ram: Vec<i16>,
+    a: i16,
+    d: i16,
+    pc: usize,
+}
+
+impl Cpu {
+    /// Assembles `asm` with the standard symbol rules: labels, predefined symbols, and
+    /// variables allocated from RAM 16.
+    pub fn new(asm: &str) -> Self {
+        let lines: Vec<String> = asm
+            .lines()
+            .map(|l| {
+                l.split("//")
+                    .next()
+                    .unwrap_or("")
+                    .replace(char::is_whitespace, "")
+            })
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        let mut symbols: HashMap<String, u16> = HashMap::new();
+        for (name, address) in [("SP", 0), ("LCL", 1), ("ARG", 2), ("THIS", 3), ("THAT", 4)] {
+            symbols.insert(name.to_string(), address);
+        }
+        for r in 0..16 {
+            symbols.insert(format!("R{r}"), r);
+        }
+        symbols.insert("SCREEN".to_string(), 16384);
+        symbols.insert("KBD".to_string(), 24576);
+
+        let mut rom_address = 0;
+        for line in &lines {
+            if let Some(label) = line.strip_prefix('(') {
+                symbols.insert(label.trim_end_matches(')').to_string(), rom_address);
+            } else {
+                rom_address += 1;
+            }
+        }
+
+        let mut next_variable = 16;
+        let mut rom = Vec::new();
+        for line in lines.iter().filter(|l| !l.starts_with('(')) {
+            if let Some(symbol) = line.strip_prefix('@') {
+                let value = match symbol.parse::<u16>() {
+                    Ok(v) => v,
+                    Err(_) => *symbols.entry(symbol.to_string()).or_insert_with(|| {
+                        next_variable += 1;
+                        next_variable - 1
+                    }),
+                };
+                rom.push(Instruction::A(value));
+            } else {
+                let (dest, rest) = line.split_once('=').unwrap_or(("", line));
+                let (comp, jump) = rest.split_once(';').unwrap_or((rest, ""));
+                rom.push(Instruction::C {
+                    dest: dest.to_string(),
+                    comp: comp.to_string(),
+                    jump: jump.to_string(),
+                });
+            }
+        }
+
+        Self {
+            rom,
+            ram: vec![0; 32768],
+            a: 0,
+            d: 0,
+            pc: 0,
+        }
+    }
+
+    fn comp(&self, comp: &str) -> i16 {
+        let m = self.ram[self.a as u16 as usize];
+        let (a, d) = (self.a, self.d);
+        match comp {
+            "0" => 0,
+            "1" => 1,
+            "-1" => -1,
+            "D" => d,
+            "A" => a,
+            "M" => m,
+            "!D" => !d,
+            "!A" => !a,
+            "!M" => !m,
+            "-D" => d.wrapping_neg(),
+            "-A" => a.wrapping_neg(),
+            "-M" => m.wrapping_neg(),
+            "D+1" => d.wrapping_add(1),
+            "A+1" => a.wrapping_add(1),
+            "M+1" => m.wrapping_add(1),
+            "D-1" => d.wrapping_sub(1),
+            "A-1" => a.wrapping_sub(1),
+            "M-1" => m.wrapping_sub(1),
+            "D+A" | "A+D" => d.wrapping_add(a),
+            "D+M" | "M+D" => d.wrapping_add(m),
+            "D-A" => d.wrapping_sub(a),
+            "D-M" => d.wrapping_sub(m),
+            "A-D" => a.wrapping_sub(d),
+            "M-D" => m.wrapping_sub(d),
+            "D&A" | "A&D" => d & a,
+            "D&M" | "M&D" => d & m,
+            "D|A" | "A|D" => d | a,
+            "D|M" | "M|D" => d | m,
+            _ => panic!("unknown comp: {comp}"),
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let Some(instruction) = self.rom.get(self.pc) else {
+            return;
+        };
+        match instruction {
+            Instruction::A(value) => {
+                self.a = *value as i16;
+                self.pc += 1;
+            }
+            Instruction::C { dest, comp, jump } => {
+                let value = self.comp(comp);
+                let address = self.a as u16 as usize;
+                if dest.contains('M') {
+                    self.ram[address] = value;
+                }
+                if dest.contains('D') {
+                    self.d = value;
+                }
+                if dest.contains('A') {
+                    self.a = value;
+                }
+                let taken = match jump.as_str() {
+                    "JGT" => value > 0,
+                    "JEQ" => value == 0,
+                    "JGE" => value >= 0,
+                    "JLT" => value < 0,
+                    "JNE" => value != 0,
+                    "JLE" => value <= 0,
+                    "JMP" => true,
+                    _ => false,
+                };
+                self.pc = if taken { address } else { self.pc + 1 };
+            }
+        }
+    }
+}
+
+/// The parts of a course test script the VM tests use: RAM setup, cycle count, and the
+/// RAM cells compared against the `.cmp` file.
+pub struct TestScript {
+    pub sets: Vec<(usize, i16)>,
+    pub cycles: usize,
+    pub outputs: Vec<usize>,
+}
+
+pub fn read_test_script(tst: &Path) -> TestScript {
+    let text = fs::read_to_string(tst).unwrap();
+    let mut script = TestScript {
+        sets: Vec::new(),
+        cycles: 0,
+        outputs: Vec::new(),
+    };
+    let mut in_output_list = false;
+
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        match words.first() {
+            Some(&"set") => script
+                .sets
+                .push((ram_address(words[1]), words[2].parse().unwrap())),
+            Some(&"repeat") => script.cycles += words[1].parse::<usize>().unwrap(),
+            Some(&"output-list") => in_output_list = true,
+            _ => {}
+        }
+        if in_output_list {
+            script.outputs.extend(
+                words
+                    .iter()
+                    .filter(|w| w.starts_with("RAM["))
+                    .map(|w| ram_address(w)),
+            );
+            in_output_list = !line.ends_with(';');
+        }
+    }
+
+    script
+}
+
+/// The expected values of a `.cmp` file, row by row, skipping the header rows.
+pub fn read_compare_file(cmp: &Path) -> Vec<i16> {
+    fs::read_to_string(cmp)
+        .unwrap()
+        .lines()
+        .flat_map(|line| {
+            line.split('|')
+                .map(str::trim)
+                .filter(|cell| !cell.is_empty())
+                .map(|cell| cell.parse::<i16>())
+                .collect::<Vec<_>>()
+        })
+        .filter_map(Result::ok)
+        .collect()
+}
+
+fn ram_address(word: &str) -> usize {
+    word.trim_start_matches("RAM[")
+        .split(']')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
@@ -0,0 +1,41 @@
+use vm_translator::code_writer::{Bootstrap, CodeWriter};
+use vm_translator::{translate, translate_file};
+
+/// A file that pops into and pushes from statics `0..count`.
+fn statics(count: usize) -> String {
+    let mut source = String::from("push constant 0\n");
+    for index in 0..count {
+        source.push_str(&format!("pop static {index}\npush static {index}\n"));
+    }
+    source
+}
+
+#[test]
+fn statics_of_all_files_share_the_segment() {
+    let (a, b) = (statics(120), statics(120));
+    assert!(translate(&[("A", &a), ("B", &b)]).is_ok());
+
+    let c = statics(1);
+    let err = translate(&[("A", &a), ("B", &b), ("C", &c)]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "static segment overflow: 241 static variables but only 240 fit in RAM 16-255"
+    );
+}
+
+#[test]
+fn report_counts_statics_per_file() {
+    let mut code_writer = CodeWriter::new(&Bootstrap::None, false).unwrap();
+    for (file, source) in [("Main", statics(3)), ("Screen", statics(12))] {
+        code_writer.current_file = Some(file.to_string());
+        translate_file(&source, &mut code_writer, None).unwrap();
+    }
+    assert_eq!(
+        code_writer.static_report(),
+        "static segment usage (RAM 16-255):
+  Main                    3
+  Screen                 12
+  total                  15 / 240
+"
+    );
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{Cpu, read_compare_file, read_test_script};
+
+/// Translates every `.vm` file in `projects/{dir}`, runs it the way `{Name}.tst` does and
+/// checks the RAM cells against `{Name}.cmp`.
+fn run_course_test(dir: &str) {
+    let test_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(dir);
+    let name = test_dir.file_name().unwrap().to_str().unwrap();
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&test_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "vm"))
+        .collect();
+    paths.sort();
+
+    let sources: Vec<(String, String)> = paths
+        .iter()
+        .map(|path| {
+            let stem = path.file_stem().unwrap().to_str().unwrap().to_string();
+            (stem, fs::read_to_string(path).unwrap())
+        })
+        .collect();
+    let inputs: Vec<(&str, &str)> = sources
+        .iter()
+        .map(|(stem, source)| (stem.as_str(), source.as_str()))
+        .collect();
+
+    let asm = vm_translator::translate(&inputs).unwrap();
+
+    let script = read_test_script(&test_dir.join(format!("{name}.tst")));
+    let expected = read_compare_file(&test_dir.join(format!("{name}.cmp")));
+
+    let mut cpu = Cpu::new(&asm);
+    for (address, value) in &script.sets {
+        cpu.ram[*address] = *value;
+    }
+    for _ in 0..script.cycles {
+        cpu.tick();
+    }
+
+    let actual: Vec<i16> = script.outputs.iter().map(|a| cpu.ram[*a]).collect();
+    assert_eq!(actual, expected, "{dir}: RAM {:?}", script.outputs);
+}
+
+#[test]
+fn simple_add() {
+    run_course_test("7/StackArithmetic/SimpleAdd");
+}
+
+#[test]
+fn stack_test() {
+    run_course_test("7/StackArithmetic/StackTest");
+}
+
+#[test]
+fn basic_test() {
+    run_course_test("7/MemoryAccess/BasicTest");
+}
+
+#[test]
+fn pointer_test() {
+    run_course_test("7/MemoryAccess/PointerTest");
+}
+
+#[test]
+fn static_test() {
+    run_course_test("7/MemoryAccess/StaticTest");
+}
+
+#[test]
+fn basic_loop() {
+    run_course_test("8/ProgramFlow/BasicLoop");
+}
+
+#[test]
+fn fibonacci_series() {
+    run_course_test("8/ProgramFlow/FibonacciSeries");
+}
+
+#[test]
+fn simple_function() {
+    run_course_test("8/FunctionCalls/SimpleFunction");
+}
+
+#[test]
+fn nested_call() {
+    run_course_test("8/FunctionCalls/NestedCall");
+}
+
+#[test]
+fn fibonacci_element() {
+    run_course_test("8/FunctionCalls/FibonacciElement");
+}
+
+#[test]
+fn statics_test() {
+    run_course_test("8/FunctionCalls/StaticsTest");
+}
+
+#[test]
+fn unknown_command_is_an_error() {
+    assert!(vm_translator::translate(&[("Bad", "push constant 1\nfrob\n")]).is_err());
+}
+
+#[test]
+fn invalid_index_is_an_error() {
+    let err =
+        vm_translator::translate(&[("Bad", "push constant 1\npush constant x\n")]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "Bad.vm:2: invalid index 'x'");
+}